@@ -0,0 +1,175 @@
+//! BM25 relevance scoring over the loaded skill corpus.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::skill::{BodySection, ExtraDoc, Skill};
+
+/// Term-frequency saturation parameter.
+const K1: f64 = 1.2;
+/// Length normalization strength (0 = none, 1 = full).
+const B: f64 = 0.75;
+
+/// Corpus-wide statistics used for BM25 scoring.
+/// Built once over all loaded skills and shared across queries.
+#[derive(Debug, Clone, Default)]
+pub struct Bm25Index {
+    /// Number of skills containing each term in any field.
+    doc_freq: HashMap<String, usize>,
+    /// Number of skills in the corpus.
+    doc_count: usize,
+    /// Average length in tokens of each body section, indexed by `BodySection`.
+    avg_section_len: [f64; 3],
+    /// Average extra doc length in tokens, across all skills.
+    avg_extra_len: f64,
+    /// Terms found in any skill's name, summary, tags or aliases.
+    routing_terms: HashSet<String>,
+}
+
+impl Bm25Index {
+    /// Build corpus statistics from a set of skills.
+    pub fn build(skills: &[Skill]) -> Self {
+        let mut doc_freq: HashMap<String, usize> = HashMap::new();
+        let mut routing_terms = HashSet::new();
+        for skill in skills {
+            routing_terms.extend(
+                skill
+                    .name_tokens
+                    .iter()
+                    .chain(&skill.summary_tokens)
+                    .chain(&skill.tag_tokens)
+                    .chain(&skill.alias_tokens)
+                    .cloned(),
+            );
+            let terms: HashSet<&str> = skill
+                .name_tokens
+                .iter()
+                .chain(&skill.summary_tokens)
+                .chain(&skill.tag_tokens)
+                .chain(&skill.alias_tokens)
+                .chain(&skill.body_tokens)
+                .chain(skill.extra_docs.iter().flat_map(|e| &e.tokens))
+                .map(String::as_str)
+                .collect();
+            for term in terms {
+                *doc_freq.entry(term.to_string()).or_insert(0) += 1;
+            }
+        }
+
+        let avg_section_len = BodySection::ALL.map(|section| {
+            let total: usize = skills.iter().map(|s| section.tokens(s).len()).sum();
+            total as f64 / skills.len().max(1) as f64
+        });
+
+        let extras: Vec<&ExtraDoc> = skills.iter().flat_map(|s| &s.extra_docs).collect();
+        let total_extra_len: usize = extras.iter().map(|e| e.tokens.len()).sum();
+        let avg_extra_len = total_extra_len as f64 / extras.len().max(1) as f64;
+
+        Self {
+            doc_freq,
+            doc_count: skills.len(),
+            avg_section_len,
+            avg_extra_len,
+            routing_terms,
+        }
+    }
+
+    /// Whether any skill contains the term.
+    pub fn contains(&self, term: &str) -> bool {
+        self.doc_freq.contains_key(term)
+    }
+
+    /// Whether the term appears in some skill's name, summary, tags or aliases.
+    pub fn is_routing_term(&self, term: &str) -> bool {
+        self.routing_terms.contains(term)
+    }
+
+    /// Inverse document frequency of a term (always non-negative).
+    pub fn idf(&self, term: &str) -> f64 {
+        let n = self.doc_count as f64;
+        let df = self.doc_freq.get(term).copied().unwrap_or(0) as f64;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    }
+
+    /// BM25 score of one section of a skill body for the given query tokens,
+    /// normalized against that section's average length.
+    /// Repeated query tokens are counted once.
+    pub fn score_section(&self, query_tokens: &[String], skill: &Skill, section: BodySection) -> f64 {
+        self.score(query_tokens, section.tokens(skill), self.avg_section_len[section as usize])
+    }
+
+    /// BM25 score of a single extra doc for the given query tokens.
+    pub fn score_extra(&self, query_tokens: &[String], extra: &ExtraDoc) -> f64 {
+        self.score(query_tokens, &extra.tokens, self.avg_extra_len)
+    }
+
+    /// BM25 score of a token field against an average field length.
+    fn score(&self, query_tokens: &[String], field_tokens: &[String], avg_len: f64) -> f64 {
+        if field_tokens.is_empty() || avg_len <= 0.0 {
+            return 0.0;
+        }
+
+        let unique_query: HashSet<&str> = query_tokens.iter().map(String::as_str).collect();
+        let mut term_freq: HashMap<&str, usize> = HashMap::new();
+        for token in field_tokens {
+            if unique_query.contains(token.as_str()) {
+                *term_freq.entry(token.as_str()).or_insert(0) += 1;
+            }
+        }
+
+        let len_norm = 1.0 - B + B * field_tokens.len() as f64 / avg_len;
+        term_freq
+            .into_iter()
+            .map(|(term, tf)| {
+                let tf = tf as f64;
+                self.idf(term) * tf * (K1 + 1.0) / (tf + K1 * len_norm)
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skill::normalized_tokens;
+
+    #[test]
+    fn test_rare_terms_have_higher_idf() {
+        let skills = vec![
+            Skill::for_test("one").body("app code storekit"),
+            Skill::for_test("two").body("app code"),
+            Skill::for_test("three").body("app code"),
+        ];
+        let index = Bm25Index::build(&skills);
+        assert!(index.idf("storekit") > index.idf("app"));
+    }
+
+    #[test]
+    fn test_long_bodies_do_not_win_on_length_alone() {
+        let long_body = format!("storekit {}", "filler ".repeat(500));
+        let skills = vec![
+            Skill::for_test("focused").body("storekit purchases"),
+            Skill::for_test("huge").body(&long_body),
+        ];
+        let index = Bm25Index::build(&skills);
+        let query = normalized_tokens("storekit");
+        assert!(
+            index.score_section(&query, &skills[0], BodySection::Prose)
+                > index.score_section(&query, &skills[1], BodySection::Prose)
+        );
+    }
+
+    #[test]
+    fn test_sections_are_normalized_separately() {
+        let mut short_heading = Skill::for_test("a");
+        short_heading.heading_tokens = normalized_tokens("storekit");
+        let mut long_prose = Skill::for_test("b").body(&format!("storekit {}", "filler ".repeat(20)));
+        long_prose.heading_tokens = normalized_tokens("overview of everything here");
+        let skills = vec![short_heading, long_prose];
+        let index = Bm25Index::build(&skills);
+        let query = normalized_tokens("storekit");
+        assert!(
+            index.score_section(&query, &skills[0], BodySection::Headings)
+                > index.score_section(&query, &skills[1], BodySection::Prose)
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Naive Bayes query classifier trained from skill `examples:`.
+//!
+//! `codex-skills train` fits per-skill token counts from example task
+//! descriptions and stores them next to the skills directory. `pick` turns
+//! them into per-skill probabilities that feed the ranking as an extra signal.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::skill::{normalized_tokens, Skill};
+
+/// Additive (Laplace) smoothing applied to every token count.
+const ALPHA: f64 = 1.0;
+
+/// Token counts gathered from one skill's examples.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ClassCounts {
+    skill: String,
+    examples: usize,
+    token_counts: BTreeMap<String, usize>,
+}
+
+/// Multinomial naive Bayes model over `normalized_tokens`.
+/// Only skills with at least one example are classes; the fingerprint
+/// covers every skill so adding, removing or editing one invalidates it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Classifier {
+    fingerprint: String,
+    classes: Vec<ClassCounts>,
+    /// Token counts of the skills without examples (name, summary, tags and
+    /// aliases), scored as an extra "other" class so a query about one of
+    /// them does not go to the trained skills by default.
+    #[serde(default)]
+    background: BTreeMap<String, usize>,
+}
+
+impl Classifier {
+    /// Fit a model from the `examples` of every skill.
+    pub fn train(skills: &[Skill]) -> Self {
+        let classes = skills
+            .iter()
+            .filter(|s| !s.examples.is_empty())
+            .map(|skill| {
+                let mut token_counts = BTreeMap::new();
+                for token in skill.examples.iter().flat_map(|e| normalized_tokens(e)) {
+                    *token_counts.entry(token).or_insert(0) += 1;
+                }
+                ClassCounts {
+                    skill: skill.name.clone(),
+                    examples: skill.examples.len(),
+                    token_counts,
+                }
+            })
+            .collect();
+        let mut background = BTreeMap::new();
+        for skill in skills.iter().filter(|s| s.examples.is_empty()) {
+            let fields = [
+                &skill.name_tokens,
+                &skill.summary_tokens,
+                &skill.tag_tokens,
+                &skill.alias_tokens,
+            ];
+            for token in fields.into_iter().flatten() {
+                *background.entry(token.clone()).or_insert(0) += 1;
+            }
+        }
+        Self {
+            fingerprint: fingerprint(skills),
+            classes,
+            background,
+        }
+    }
+
+    /// Number of training examples across all classes.
+    pub fn example_count(&self) -> usize {
+        self.classes.iter().map(|c| c.examples).sum()
+    }
+
+    /// Number of skills the model can predict.
+    pub fn class_count(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Whether the model was trained on exactly this skill set.
+    pub fn is_current(&self, skills: &[Skill]) -> bool {
+        self.fingerprint == fingerprint(skills)
+    }
+
+    /// Posterior probability of each class skill for the query tokens, with a
+    /// uniform prior. The "other" class of untrained skills takes its share of
+    /// the probability mass but is not returned, so the values may sum to less
+    /// than 1. Tokens never seen in training are ignored; returns `None` when
+    /// none are known.
+    pub fn probabilities(&self, query_tokens: &[String]) -> Option<HashMap<String, f64>> {
+        let vocabulary: HashSet<&str> = self
+            .classes
+            .iter()
+            .map(|c| &c.token_counts)
+            .chain(Some(&self.background))
+            .flat_map(|counts| counts.keys().map(String::as_str))
+            .collect();
+        let known: Vec<&str> = query_tokens
+            .iter()
+            .map(String::as_str)
+            .filter(|t| vocabulary.contains(t))
+            .collect();
+        if known.is_empty() {
+            return None;
+        }
+
+        let vocab_size = vocabulary.len() as f64;
+        let log_likelihood = |token_counts: &BTreeMap<String, usize>| -> f64 {
+            let total: usize = token_counts.values().sum();
+            let denominator = total as f64 + ALPHA * vocab_size;
+            known
+                .iter()
+                .map(|t| {
+                    let count = token_counts.get(*t).copied().unwrap_or(0);
+                    ((count as f64 + ALPHA) / denominator).ln()
+                })
+                .sum()
+        };
+        let log_likelihoods: Vec<f64> =
+            self.classes.iter().map(|class| log_likelihood(&class.token_counts)).collect();
+        let background = (!self.background.is_empty()).then(|| log_likelihood(&self.background));
+
+        // Softmax with the max subtracted for numerical stability.
+        let max = log_likelihoods
+            .iter()
+            .chain(&background)
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let exps: Vec<f64> = log_likelihoods.iter().map(|l| (l - max).exp()).collect();
+        let sum: f64 = exps.iter().sum::<f64>() + background.map_or(0.0, |l| (l - max).exp());
+        Some(
+            self.classes
+                .iter()
+                .zip(exps)
+                .map(|(class, e)| (class.skill.clone(), e / sum))
+                .collect(),
+        )
+    }
+
+    /// Load a model file; returns `None` if it does not exist.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read model file {}", path.display()))?;
+        let model = serde_json::from_str(&contents).with_context(|| {
+            format!(
+                "Invalid model file {} (re-run `codex-skills train`)",
+                path.display()
+            )
+        })?;
+        Ok(Some(model))
+    }
+
+    /// Write the model as JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+            .with_context(|| format!("Failed to write model file {}", path.display()))
+    }
+}
+
+/// Model file location for a skills directory: a sibling file named after
+/// it, e.g. `skills` -> `skills.model.json`.
+pub fn model_path(skills_dir: &Path) -> PathBuf {
+    // Collecting components drops any trailing separator.
+    let mut path = skills_dir.components().collect::<PathBuf>().into_os_string();
+    path.push(".model.json");
+    PathBuf::from(path)
+}
+
+/// Stable FNV-1a hash of every skill's name, examples, summary, tags and
+/// aliases, in name order.
+fn fingerprint(skills: &[Skill]) -> String {
+    let mut sorted: Vec<&Skill> = skills.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for skill in sorted {
+        let texts = std::iter::once(&skill.name)
+            .chain(&skill.examples)
+            .chain(Some(&skill.summary))
+            .chain(&skill.keywords)
+            .chain(&skill.aliases);
+        for text in texts {
+            for byte in text.bytes().chain(std::iter::once(0)) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+        hash ^= 0xff;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skills() -> Vec<Skill> {
+        vec![
+            Skill::for_test("release-notes").examples(&[
+                "write the changelog for this version",
+                "summarize merged pull requests",
+            ]),
+            Skill::for_test("db-migrations").examples(&[
+                "add a column to the users table",
+                "roll back a schema migration",
+            ]),
+            Skill::for_test("no-examples"),
+        ]
+    }
+
+    #[test]
+    fn test_probabilities_favor_the_skill_with_matching_examples() {
+        let model = Classifier::train(&skills());
+        assert_eq!(model.class_count(), 2);
+        assert_eq!(model.example_count(), 4);
+
+        let probs = model.probabilities(&normalized_tokens("changelog version")).unwrap();
+        // The untrained "no-examples" skill takes part of the mass.
+        assert!(probs["release-notes"] > 0.5);
+        assert!(probs["release-notes"] > 3.0 * probs["db-migrations"]);
+        assert!(!probs.contains_key("no-examples"));
+        let total: f64 = probs.values().sum();
+        assert!(total <= 1.0 + 1e-9);
+    }
+
+    #[test]
+    fn test_untrained_skills_keep_a_lone_class_from_certainty() {
+        let skills = vec![
+            Skill::for_test("release-notes")
+                .examples(&["write the changelog for this version"]),
+            Skill::for_test("db-migrations").summary("Evolve the database schema safely"),
+        ];
+        let model = Classifier::train(&skills);
+        assert_eq!(model.class_count(), 1);
+
+        let about_other = model.probabilities(&normalized_tokens("database schema")).unwrap();
+        assert!(about_other["release-notes"] < 0.5, "got {about_other:?}");
+        let about_trained = model.probabilities(&normalized_tokens("changelog")).unwrap();
+        assert!(about_trained["release-notes"] > 0.5, "got {about_trained:?}");
+    }
+
+    #[test]
+    fn test_unknown_tokens_give_no_prediction() {
+        let model = Classifier::train(&skills());
+        assert!(model.probabilities(&normalized_tokens("quantum banana")).is_none());
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_skill_set_and_examples() {
+        let mut current = skills();
+        let model = Classifier::train(&current);
+        assert!(model.is_current(&current));
+
+        current.reverse();
+        assert!(model.is_current(&current));
+
+        current[0].examples.push("another example".to_string());
+        assert!(!model.is_current(&current));
+        let mut edited = skills();
+        edited[2].summary = "Now with a summary".to_string();
+        assert!(!model.is_current(&edited));
+        assert!(!model.is_current(&skills()[..2]));
+    }
+
+    #[test]
+    fn test_model_path_is_a_sibling_of_the_skills_dir() {
+        assert_eq!(model_path(Path::new("skills")), PathBuf::from("skills.model.json"));
+        assert_eq!(
+            model_path(Path::new("/srv/my.skills/")),
+            PathBuf::from("/srv/my.skills.model.json")
+        );
+    }
+}
@@ -1,9 +1,28 @@
 //! Command implementations for the CLI.
 
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::Path;
 
-use crate::matching::{closest_skill_names, rank_skills};
-use crate::skill::{find_skill, Skill};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::bm25::Bm25Index;
+use crate::classifier::{model_path, Classifier};
+use crate::config::{Config, RankingConfig};
+use crate::context::RepoContext;
+use crate::diversity::mmr_rerank;
+use crate::eval::{EvalReport, GoldenCase};
+use crate::glossary::Glossary;
+use crate::history::History;
+use crate::matching::{
+    closest_skill_names, explain_tokens, margin, rank_skills, relative_score, split_intents,
+    verdict, PreparedQuery,
+    SkillSignals, TokenOrigin, Verdict,
+};
+use crate::skill::{find_skill, find_skill_exact, Skill};
+use crate::spelling::{corrected_query, Vocabulary};
+use crate::synonyms::Synonyms;
+use crate::syntax::QuerySyntax;
 
 /// Print a separator line.
 pub fn separator() -> String {
@@ -40,54 +59,404 @@ pub fn cmd_list(skills: &[Skill], brief: bool, verbose: bool, json: bool, clip:
     }
 }
 
-/// Execute the `pick` command.
-pub fn cmd_pick(skills: &[Skill], query: &str, top: usize, show: bool) {
-    let ranked = rank_skills(skills, query);
+/// Print a document block with an optional title after the separator.
+fn print_doc(title: Option<&str>, contents: &str) {
+    match title {
+        Some(title) => println!("\n{} {}\n{}\n", separator(), title, contents.trim()),
+        None => println!("\n{}\n{}\n", separator(), contents.trim()),
+    }
+}
+
+/// Ranking strategy for the `pick` command.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum PickMode {
+    /// Weighted token, phrase and similarity signals
+    #[default]
+    Lexical,
+    /// Lexical score plus an offline TF-IDF cosine similarity bonus
+    Semantic,
+}
+
+/// Display options for the `pick` command.
+#[derive(Debug, Clone, Default)]
+pub struct PickOptions {
+    /// Ranking strategy
+    pub mode: PickMode,
+    /// Number of candidates to show
+    pub top: usize,
+    /// Print the full playbook for the top result
+    pub show: bool,
+    /// Print only the best-matching document for the top result
+    pub focused: bool,
+    /// Print a per-token contribution table for each result
+    pub explain: bool,
+    /// Project markers detected for `--context`
+    pub context: RepoContext,
+    /// Rank each intent of a multi-part query separately
+    pub split: bool,
+    /// MMR trade-off (0-1) between relevance and variety in the top results
+    pub diversity: f64,
+    /// Earlier conversation turns for `--history`
+    pub history: History,
+    /// Parse phrases, exclusions and field prefixes in queries; off for text
+    /// read from files, stdin, diffs and batches
+    pub query_syntax: bool,
+}
+
+/// Print the per-token contribution table used by `pick --explain`.
+fn print_explanation(
+    skill: &Skill,
+    score: usize,
+    signals: &SkillSignals,
+    index: &Bm25Index,
+    query: &PreparedQuery,
+    ranking: &RankingConfig,
+) {
+    let rows = explain_tokens(skill, index, query, signals, ranking);
+    println!("   {:<24} {:<34} {:>12}", "token", "fields", "contribution");
+    for row in &rows {
+        let token = match row.origin {
+            TokenOrigin::Query => row.token.clone(),
+            TokenOrigin::Expanded => format!("{} (expanded)", row.token),
+            TokenOrigin::Corrected => format!("{} (corrected)", row.token),
+        };
+        let fields = if row.fields.is_empty() {
+            "-".to_string()
+        } else {
+            row.fields.join(", ")
+        };
+        println!("   {:<24} {:<34} {:>12.1}", token, fields, row.contribution);
+    }
+    for (label, value) in [
+        ("+ phrase bonus", ranking.phrase_weight * signals.phrase_bonus),
+        ("+ phrase hits", ranking.phrase_hit_weight * signals.phrase_hits),
+        ("+ name similarity", ranking.name_similarity_weight * signals.name_similarity),
+        (
+            "+ summary similarity",
+            ranking.summary_similarity_weight * signals.summary_similarity,
+        ),
+        ("+ model score", ranking.model_weight * signals.model_score),
+        ("+ context boost", ranking.context_weight * signals.context_hits),
+        ("+ history", ranking.history_weight * signals.history_score),
+        ("+ stickiness", ranking.stickiness_weight * signals.sticky),
+        ("- avoid penalty", signals.penalty(ranking)),
+    ] {
+        println!("   {:<59} {:>12}", label, value);
+    }
+    println!("   {:<59} {:>12}", "= total", score);
+}
+
+/// Corpus indexes and settings shared by every query ranked in one run.
+struct Router<'a> {
+    skills: &'a [Skill],
+    index: Bm25Index,
+    vocabulary: Vocabulary,
+    #[cfg(feature = "semantic")]
+    semantic: Option<crate::semantic::SemanticIndex>,
+    options: &'a PickOptions,
+    glossary: &'a Glossary,
+    synonyms: &'a Synonyms,
+    ranking: &'a RankingConfig,
+    model: Option<&'a Classifier>,
+}
+
+impl<'a> Router<'a> {
+    /// Build indexes once over `skills`; the TF-IDF index only in semantic mode.
+    fn new(
+        skills: &'a [Skill],
+        options: &'a PickOptions,
+        glossary: &'a Glossary,
+        synonyms: &'a Synonyms,
+        ranking: &'a RankingConfig,
+        model: Option<&'a Classifier>,
+    ) -> Self {
+        Self {
+            skills,
+            index: Bm25Index::build(skills),
+            vocabulary: Vocabulary::build(skills),
+            #[cfg(feature = "semantic")]
+            semantic: (options.mode == PickMode::Semantic)
+                .then(|| crate::semantic::SemanticIndex::build(skills)),
+            options,
+            glossary,
+            synonyms,
+            ranking,
+            model,
+        }
+    }
+
+    /// Prepare a raw query and rank every skill against it.
+    fn rank(&self, query: &str) -> (PreparedQuery, Vec<(usize, &'a Skill, SkillSignals)>) {
+        let syntax = if self.options.query_syntax {
+            QuerySyntax::parse(query)
+        } else {
+            QuerySyntax::plain(query)
+        };
+        let mut prepared = PreparedQuery::new(syntax, self.glossary, self.synonyms, &self.vocabulary);
+        prepared.keep_distinctive(&self.index, self.synonyms, self.ranking.long_query_terms);
+        prepared.add_history(&self.options.history, self.ranking.history_decay);
+        let mut ranked = rank_skills(
+            self.skills,
+            &self.index,
+            &prepared,
+            self.ranking,
+            self.model,
+            &self.options.context,
+        );
+        #[cfg(feature = "semantic")]
+        if let Some(semantic) = &self.semantic {
+            let reference =
+                crate::matching::confidence_reference(prepared.tokens.len(), self.ranking);
+            crate::semantic::rerank(
+                &mut ranked,
+                semantic,
+                &prepared,
+                self.ranking.semantic_blend,
+                reference,
+            );
+        }
+        mmr_rerank(&mut ranked, &self.index, self.options.top, self.options.diversity);
+        (prepared, ranked)
+    }
+}
+
+/// Print `pick --split` results: a ranked list per intent, then the top
+/// confident pick of each intent with duplicate skills merged.
+fn print_split(router: &Router, query: &str, options: &PickOptions, ranking: &RankingConfig) {
+    let intents = split_intents(query);
+    if intents.is_empty() {
+        println!("No good skill match for '{}'. Try a broader or simpler description.", query);
+        return;
+    }
 
-    if let Some((best_score, _, _)) = ranked.first() {
-        if *best_score == 0 {
-            let shortlist = closest_skill_names(skills, query, 5);
+    // Merged picks in intent order: (skill name, intents it answers).
+    let mut merged: Vec<(&str, Vec<usize>)> = Vec::new();
+    for (n, intent) in intents.iter().enumerate() {
+        println!("Intent {}: {}", n + 1, intent);
+        let (prepared, ranked) = router.rank(intent);
+        let relative: Vec<f64> = ranked
+            .iter()
+            .map(|(score, _, _)| relative_score(*score, prepared.tokens.len(), ranking))
+            .collect();
+        let confidences: Vec<f64> = relative.iter().map(|r| r.min(1.0)).collect();
+        let verdict = verdict(&relative, ranking);
+        if verdict == Verdict::NoMatch {
+            println!("   (no good match)");
+            continue;
+        }
+        let listed = ranked
+            .iter()
+            .zip(&confidences)
+            .take(options.top)
+            .filter(|((score, _, _), _)| *score > 0);
+        for (idx, ((score, skill, _), confidence)) in listed.enumerate() {
             println!(
-                "No good skill match for '{}'. Try a broader or simpler description.\nClosest skill names: {}",
-                query,
-                if shortlist.is_empty() {
-                    "(no close names found)".to_string()
-                } else {
-                    shortlist.join(", ")
-                }
+                "   {}. {} (score: {}, confidence: {:.2})",
+                idx + 1,
+                skill.name,
+                score,
+                confidence
             );
-            return;
         }
+        let top = ranked[0].1.name.as_str();
+        match merged.iter_mut().find(|(name, _)| *name == top) {
+            Some((_, answered)) => answered.push(n + 1),
+            None => merged.push((top, vec![n + 1])),
+        }
+    }
+
+    if !merged.is_empty() {
+        let picks: Vec<String> = merged
+            .iter()
+            .map(|(name, answered)| {
+                let list: Vec<String> = answered.iter().map(usize::to_string).collect();
+                let label = if answered.len() == 1 { "intent" } else { "intents" };
+                format!("{} ({} {})", name, label, list.join(", "))
+            })
+            .collect();
+        println!("Skills to use: {}", picks.join("; "));
+    }
+}
+
+/// Execute the `pick` command.
+/// With `show`, the top result's best-matching extra doc is printed first,
+/// followed by the playbook and remaining docs; `focused` prints only that doc.
+/// `model` is a trained classifier already checked against `skills`.
+pub fn cmd_pick(
+    skills: &[Skill],
+    query: &str,
+    options: &PickOptions,
+    glossary: &Glossary,
+    synonyms: &Synonyms,
+    ranking: &RankingConfig,
+    model: Option<&Classifier>,
+) {
+    let router = Router::new(skills, options, glossary, synonyms, ranking, model);
+    if options.split {
+        print_split(&router, query, options, ranking);
+        return;
+    }
+    let (prepared, ranked) = router.rank(query);
+    let index = &router.index;
+    let focused = options.focused;
+    let show = options.show || focused;
+
+    if options.explain
+        && let Some(dir) = &options.context.dir
+    {
+        let markers: Vec<&str> = options.context.markers.iter().map(String::as_str).collect();
+        println!(
+            "Context markers in {}: {}",
+            dir.display(),
+            if markers.is_empty() {
+                "(none)".to_string()
+            } else {
+                markers.join(", ")
+            }
+        );
+    }
+    if options.explain && !options.history.turns.is_empty() {
+        let recent: Vec<String> = prepared
+            .history
+            .iter()
+            .take(8)
+            .map(|(token, weight)| format!("{} ({:.2})", token, weight))
+            .collect();
+        let turns = options.history.turns.len();
+        println!(
+            "History: {} {}, previous skill: {}, tokens: {}",
+            turns,
+            if turns == 1 { "turn" } else { "turns" },
+            prepared.previous_skill.as_deref().unwrap_or("(none)"),
+            if recent.is_empty() {
+                "(none)".to_string()
+            } else {
+                recent.join(", ")
+            }
+        );
+    }
+    if options.explain && !prepared.syntax.is_plain() {
+        println!("Query filters: {}", prepared.syntax.describe());
+    }
+    if prepared.reduced {
+        println!("Using distinctive terms: {}", prepared.tokens.join(", "));
+    }
+    if !prepared.corrections.is_empty() {
+        println!(
+            "Did you mean: {}",
+            corrected_query(query, &prepared.corrections)
+        );
+    }
+
+    let token_count = prepared.tokens.len();
+    let relative: Vec<f64> = ranked
+        .iter()
+        .map(|(score, _, _)| relative_score(*score, token_count, ranking))
+        .collect();
+    let confidences: Vec<f64> = relative.iter().map(|r| r.min(1.0)).collect();
+    let verdict = verdict(&relative, ranking);
+
+    if verdict == Verdict::NoMatch {
+        let shortlist = closest_skill_names(skills, &prepared.syntax.text, 5);
+        println!(
+            "No good skill match for '{}'. Try a broader or simpler description.\nClosest skill names: {}",
+            clip_summary(&query.split_whitespace().collect::<Vec<_>>().join(" "), 80),
+            if shortlist.is_empty() {
+                "(no close names found)".to_string()
+            } else {
+                shortlist.join(", ")
+            }
+        );
+        println!("Verdict: {}", verdict.label());
+        return;
     }
 
     let mut shown = false;
-    for (idx, (score, skill, signals)) in ranked.iter().take(top).enumerate() {
+    let listed = ranked
+        .iter()
+        .zip(&confidences)
+        .take(options.top)
+        .filter(|((score, _, _), _)| *score > 0);
+    for (idx, ((score, skill, signals), confidence)) in listed.enumerate() {
         println!(
-            "{}. {} (score: {}) — {}",
+            "{}. {} (score: {}, confidence: {:.2}) — {}",
             idx + 1,
             skill.name,
             score,
+            confidence,
             skill.summary
         );
+        let best_extra = signals
+            .best_extra
+            .as_deref()
+            .and_then(|name| skill.extra_docs.iter().find(|e| e.name == name));
+        if let Some(extra) = best_extra {
+            println!("   best doc: {}", extra.name);
+        }
+        if options.explain {
+            print_explanation(skill, *score, signals, index, &prepared, ranking);
+        }
         if show && idx == 0 {
-            println!("\n{}\n{}\n", separator(), skill.doc.trim());
+            if let Some(extra) = best_extra {
+                print_doc(Some(&extra.name), &extra.contents);
+            }
+            if !focused || best_extra.is_none() {
+                print_doc(None, &skill.doc);
+            }
             println!(
-                "Top match reasoning: name hits={}, summary hits={}, tag hits={}, body hits={}, phrase bonus={}, name similarity={}, summary similarity={}",
+                "Top match reasoning: name hits={}, summary hits={}, tag hits={}, alias hits={}, expanded hits={}, fuzzy hits={}, body hits={}, heading bm25={}, prose bm25={}, code bm25={}, extras hits={}{}, phrase bonus={}, phrase hits={}, name similarity={}, summary similarity={}, avoid penalty={}{}{}{}{}",
                 signals.name_hits,
                 signals.summary_hits,
                 signals.tag_hits,
+                signals.alias_hits,
+                signals.expanded_hits,
+                signals.fuzzy_hits,
                 signals.body_hits,
+                signals.heading_bm25,
+                signals.prose_bm25,
+                signals.code_bm25,
+                signals.extras_hits,
+                signals
+                    .best_extra
+                    .as_deref()
+                    .map(|name| format!(" (best: {})", name))
+                    .unwrap_or_default(),
                 signals.phrase_bonus,
+                signals.phrase_hits,
                 signals.name_similarity,
                 signals.summary_similarity,
+                signals.penalty(ranking),
+                if model.is_some() {
+                    format!(", model score={}", signals.model_score)
+                } else {
+                    String::new()
+                },
+                if options.context.dir.is_some() {
+                    format!(", context hits={}", signals.context_hits)
+                } else {
+                    String::new()
+                },
+                if options.history.turns.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        ", history score={}, sticky={}",
+                        signals.history_score, signals.sticky
+                    )
+                },
+                if options.mode == PickMode::Semantic {
+                    format!(", semantic similarity={}%", signals.semantic_similarity)
+                } else {
+                    String::new()
+                },
             );
-            for extra in &skill.extra_docs {
-                println!(
-                    "\n{} {}\n{}\n",
-                    separator(),
-                    extra.name,
-                    extra.contents.trim()
-                );
+            if !focused {
+                for extra in &skill.extra_docs {
+                    if best_extra.is_some_and(|best| best.name == extra.name) {
+                        continue;
+                    }
+                    print_doc(Some(&extra.name), &extra.contents);
+                }
             }
             shown = true;
         }
@@ -96,6 +465,252 @@ pub fn cmd_pick(skills: &[Skill], query: &str, top: usize, show: bool) {
     if show && !shown {
         println!("No matches to display; try a broader query.");
     }
+
+    let margin = margin(&relative);
+    match verdict {
+        Verdict::Ambiguous => println!(
+            "Verdict: {} (top matches within {:.2} confidence; ask the user to choose)",
+            verdict.label(),
+            margin
+        ),
+        _ => println!(
+            "Verdict: {} (confidence {:.2}, margin {:.2})",
+            verdict.label(),
+            confidences[0],
+            margin
+        ),
+    }
+}
+
+/// Execute the `train` command: fit the query classifier from skill
+/// examples and write it next to the skills directory.
+pub fn cmd_train(skills: &[Skill], skills_dir: &Path) -> Result<()> {
+    let model = Classifier::train(skills);
+    if model.example_count() == 0 {
+        bail!("No skill defines `examples:` in its frontmatter; nothing to train on");
+    }
+    let path = model_path(skills_dir);
+    model.save(&path)?;
+    println!(
+        "Trained on {} examples across {} skills; model written to {}",
+        model.example_count(),
+        model.class_count(),
+        path.display()
+    );
+    let untrained: Vec<&str> = skills
+        .iter()
+        .filter(|s| s.examples.is_empty())
+        .map(|s| s.name.as_str())
+        .collect();
+    if !untrained.is_empty() {
+        println!("Skills without examples: {}", untrained.join(", "));
+    }
+    Ok(())
+}
+
+/// One input line of `pick --batch` given as a JSON object.
+#[derive(Debug, Deserialize)]
+struct BatchQuery {
+    #[serde(default)]
+    id: Option<serde_json::Value>,
+    query: String,
+}
+
+/// One output line of `pick --batch`.
+#[derive(Debug, Serialize)]
+struct BatchResult<'a> {
+    id: serde_json::Value,
+    query: &'a str,
+    verdict: &'static str,
+    results: Vec<BatchMatch<'a>>,
+}
+
+/// Output line of `pick --batch` for an input line that could not be parsed.
+#[derive(Debug, Serialize)]
+struct BatchError {
+    line: usize,
+    error: String,
+}
+
+/// A ranked skill within a batch result.
+#[derive(Debug, Serialize)]
+struct BatchMatch<'a> {
+    skill: &'a str,
+    score: usize,
+    confidence: f64,
+    signals: &'a SkillSignals,
+}
+
+/// Execute `pick --batch`: rank every query in `input` (a file, or `-` for
+/// stdin) against skills loaded once, writing one JSON result per line.
+/// Lines starting with `{` are JSON objects with a `query` and optional `id`;
+/// any other non-empty line is a bare query identified by its line number.
+/// A malformed line yields a `{"line": N, "error": "..."}` record instead of
+/// stopping the run; the command fails at the end if any line did.
+pub fn cmd_pick_batch(
+    skills: &[Skill],
+    input: &Path,
+    options: &PickOptions,
+    glossary: &Glossary,
+    synonyms: &Synonyms,
+    ranking: &RankingConfig,
+    model: Option<&Classifier>,
+) -> Result<()> {
+    let reader: Box<dyn BufRead> = if input == Path::new("-") {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        let file = std::fs::File::open(input)
+            .with_context(|| format!("Failed to open batch file {}", input.display()))?;
+        Box::new(BufReader::new(file))
+    };
+
+    let router = Router::new(skills, options, glossary, synonyms, ranking, model);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    let mut failed = 0;
+    for (n, line) in reader.lines().enumerate() {
+        let line = line.with_context(|| format!("Failed to read batch input line {}", n + 1))?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let entry = if line.starts_with('{') {
+            match serde_json::from_str(line) {
+                Ok(entry) => entry,
+                Err(err) => {
+                    failed += 1;
+                    let error = BatchError {
+                        line: n + 1,
+                        error: format!("Invalid batch entry: {}", err),
+                    };
+                    serde_json::to_writer(&mut out, &error)?;
+                    writeln!(out)?;
+                    continue;
+                }
+            }
+        } else {
+            BatchQuery {
+                id: None,
+                query: line.to_string(),
+            }
+        };
+
+        let (prepared, ranked) = router.rank(&entry.query);
+        let relative: Vec<f64> = ranked
+            .iter()
+            .map(|(score, _, _)| relative_score(*score, prepared.tokens.len(), ranking))
+            .collect();
+        let confidences: Vec<f64> = relative.iter().map(|r| r.min(1.0)).collect();
+        let results = ranked
+            .iter()
+            .zip(&confidences)
+            .take(options.top)
+            .filter(|((score, _, _), _)| *score > 0)
+            .map(|((score, skill, signals), confidence)| BatchMatch {
+                skill: &skill.name,
+                score: *score,
+                confidence: *confidence,
+                signals,
+            })
+            .collect();
+        let result = BatchResult {
+            id: entry.id.unwrap_or_else(|| (n + 1).into()),
+            query: &entry.query,
+            verdict: verdict(&relative, ranking).label(),
+            results,
+        };
+        serde_json::to_writer(&mut out, &result)?;
+        writeln!(out)?;
+    }
+    out.flush()?;
+    if failed > 0 {
+        bail!("{} batch line(s) could not be parsed", failed);
+    }
+    Ok(())
+}
+
+/// Execute the `eval` command: rank every golden query and report
+/// precision@1, precision@k, MRR and the most-mistaken skill pairs.
+/// Exits non-zero when a metric falls below its configured threshold.
+pub fn cmd_eval(
+    skills: &[Skill],
+    cases: &[GoldenCase],
+    k: usize,
+    glossary: &Glossary,
+    synonyms: &Synonyms,
+    model: Option<&Classifier>,
+    config: &Config,
+) -> Result<()> {
+    let (ranking, thresholds) = (&config.ranking, &config.eval);
+    if cases.is_empty() {
+        bail!("Golden file has no queries to evaluate");
+    }
+    // Resolve expected names (or aliases) to canonical skill names up front.
+    // Only exact matches count: a partial name could silently pick a
+    // different skill than the golden file meant.
+    let mut resolved = Vec::with_capacity(cases.len());
+    for case in cases {
+        let mut expected = Vec::new();
+        for name in &case.expected {
+            let Some(skill) = find_skill_exact(skills, name) else {
+                bail!("Golden query '{}' expects unknown skill '{}'", case.query, name);
+            };
+            expected.push(skill.name.clone());
+        }
+        resolved.push(GoldenCase {
+            query: case.query.clone(),
+            expected,
+        });
+    }
+
+    // Plain lexical ranking: golden results must not depend on the directory
+    // eval runs in or on display options.
+    let options = PickOptions::default();
+    let router = Router::new(skills, &options, glossary, synonyms, ranking, model);
+    let results: Vec<(&GoldenCase, Vec<&str>)> = resolved
+        .iter()
+        .map(|case| {
+            let ranked = router
+                .rank(&case.query)
+                .1
+                .into_iter()
+                .filter(|(score, _, _)| *score > 0)
+                .map(|(_, skill, _)| skill.name.as_str())
+                .collect();
+            (case, ranked)
+        })
+        .collect();
+    let report = EvalReport::new(&results, k);
+
+    println!("Evaluated {} queries (k={})", report.cases, report.k);
+    println!("{}", separator());
+    let metrics = [
+        ("precision@1".to_string(), report.precision_at_1, thresholds.min_precision_at_1),
+        (format!("precision@{}", k), report.precision_at_k, thresholds.min_precision_at_k),
+        ("MRR".to_string(), report.mrr, thresholds.min_mrr),
+    ];
+    for (label, value, _) in &metrics {
+        println!("{:<14} {:.3}", label, value);
+    }
+    if !report.confusions.is_empty() {
+        println!("\nMost-mistaken pairs (expected -> picked):");
+        for (wanted, got, count) in report.confusions.iter().take(10) {
+            println!("  {} -> {} ({})", wanted, got, count);
+        }
+    }
+
+    let failures: Vec<String> = metrics
+        .iter()
+        .filter(|(_, value, min)| value < min)
+        .map(|(label, value, min)| format!("{} {:.3} is below {:.3}", label, value, min))
+        .collect();
+    if !failures.is_empty() {
+        println!();
+        for failure in &failures {
+            println!("  ✗ FAIL: {}", failure);
+        }
+        std::process::exit(1);
+    }
+    Ok(())
 }
 
 /// Execute the `show` command.
@@ -1,9 +1,14 @@
 //! Configuration file support for codex-skills.
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use anyhow::{bail, Context, Result};
 use serde::Deserialize;
 
+use crate::glossary::Glossary;
+use crate::skill::BodySection;
+
 /// Configuration options for codex-skills.
 #[derive(Debug, Deserialize, Default)]
 #[serde(default)]
@@ -14,12 +19,165 @@ pub struct Config {
     pub clip_length: usize,
     /// Default skills directory
     pub skills_dir: Option<PathBuf>,
+    /// Detect project markers in the current directory for every pick
+    pub detect_context: bool,
+    /// Synonym groups used to expand pick queries (e.g. `ios = ["iphone"]`)
+    pub synonyms: HashMap<String, Vec<String>>,
+    /// TOML file of non-English terms and their English translations,
+    /// relative to the config file
+    pub glossary: Option<PathBuf>,
+    /// Ranking weights and similarity thresholds
+    pub ranking: RankingConfig,
+    /// Minimum metrics required by `codex-skills eval`
+    pub eval: EvalConfig,
+}
+
+/// Tunable weights and thresholds used when ranking skills.
+/// Unset keys keep the built-in defaults; unknown keys are rejected.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RankingConfig {
+    pub name_weight: usize,
+    pub summary_weight: usize,
+    pub tag_weight: usize,
+    pub alias_weight: usize,
+    pub expanded_weight: usize,
+    /// Weight per typo-corrected query token matching a skill
+    pub fuzzy_weight: usize,
+    /// Weight of BM25 relevance in body headings
+    pub heading_weight: usize,
+    /// Weight of BM25 relevance in body paragraphs and list items
+    pub prose_weight: usize,
+    /// Weight of BM25 relevance in body code blocks
+    pub code_weight: usize,
+    pub extras_weight: usize,
+    pub phrase_weight: usize,
+    /// Weight per query bigram/trigram found verbatim in a skill
+    pub phrase_hit_weight: usize,
+    pub name_similarity_weight: usize,
+    pub summary_similarity_weight: usize,
+    /// Penalty per `avoid_when` trigger found in the query
+    pub avoid_penalty: usize,
+    /// Weight of the trained classifier's probability (scaled to 0-10)
+    pub model_weight: usize,
+    /// Weight per `applies_to` marker found in the project context
+    pub context_weight: usize,
+    /// Weight per tenth of a decayed history token hit (`pick --history`)
+    pub history_weight: usize,
+    /// Weight multiplier (0-1) per turn of age for history tokens
+    pub history_decay: f64,
+    /// Boost for the skill selected in the previous turn (0 = off)
+    pub stickiness_weight: usize,
+    /// Longer queries keep only this many most distinctive terms (0 = all)
+    pub long_query_terms: usize,
+    /// Share (0-1) of content variety in the top results (0 = plain ranking)
+    pub diversity: f64,
+    /// Weight (0-1) of the TF-IDF similarity bonus in `pick --mode semantic`
+    pub semantic_blend: f64,
+    /// Minimum confidence (0-1) for the top result to count as a match
+    pub min_confidence: f64,
+    /// Confidence gap (0-1) between #1 and #2 below which a pick is ambiguous
+    pub ambiguity_margin: f64,
+    /// Minimum name similarity (0-1) trusted without token agreement
+    pub name_similarity_gate: f64,
+    /// Minimum summary similarity (0-1) trusted without token agreement
+    pub summary_similarity_gate: f64,
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            name_weight: 8,
+            summary_weight: 5,
+            tag_weight: 4,
+            alias_weight: 6,
+            expanded_weight: 3,
+            fuzzy_weight: 3,
+            heading_weight: 3,
+            prose_weight: 1,
+            code_weight: 1,
+            extras_weight: 3,
+            phrase_weight: 1,
+            phrase_hit_weight: 4,
+            name_similarity_weight: 2,
+            summary_similarity_weight: 1,
+            avoid_penalty: 10,
+            model_weight: 3,
+            context_weight: 6,
+            history_weight: 1,
+            history_decay: 0.5,
+            stickiness_weight: 5,
+            long_query_terms: 12,
+            diversity: 0.0,
+            semantic_blend: 0.5,
+            min_confidence: 0.4,
+            ambiguity_margin: 0.05,
+            name_similarity_gate: 0.92,
+            summary_similarity_gate: 0.94,
+        }
+    }
+}
+
+impl RankingConfig {
+    /// Weight of a body section's BM25 signal.
+    pub fn section_weight(&self, section: BodySection) -> usize {
+        match section {
+            BodySection::Headings => self.heading_weight,
+            BodySection::Prose => self.prose_weight,
+            BodySection::Code => self.code_weight,
+        }
+    }
+
+    /// Check that thresholds are within their valid ranges.
+    pub fn validate(&self) -> Result<()> {
+        for (key, value) in [
+            ("history_decay", self.history_decay),
+            ("diversity", self.diversity),
+            ("semantic_blend", self.semantic_blend),
+            ("min_confidence", self.min_confidence),
+            ("ambiguity_margin", self.ambiguity_margin),
+            ("name_similarity_gate", self.name_similarity_gate),
+            ("summary_similarity_gate", self.summary_similarity_gate),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                bail!("ranking.{} must be between 0 and 1 (got {})", key, value);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Metric thresholds (0-1) below which `codex-skills eval` fails.
+/// All default to 0, so evaluation only reports until thresholds are set.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EvalConfig {
+    pub min_precision_at_1: f64,
+    pub min_precision_at_k: f64,
+    pub min_mrr: f64,
+}
+
+impl EvalConfig {
+    /// Check that thresholds are within their valid ranges.
+    pub fn validate(&self) -> Result<()> {
+        for (key, value) in [
+            ("min_precision_at_1", self.min_precision_at_1),
+            ("min_precision_at_k", self.min_precision_at_k),
+            ("min_mrr", self.min_mrr),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                bail!("eval.{} must be between 0 and 1 (got {})", key, value);
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Config {
     /// Load configuration from the default config file location.
-    /// Returns default config if file doesn't exist.
-    pub fn load() -> Self {
+    /// Returns default config if file doesn't exist, and an error if the
+    /// first file found cannot be read, parsed or validated.
+    pub fn load() -> Result<Self> {
         Self::load_from_paths(&[
             // Current directory
             PathBuf::from(".codex-skills.toml"),
@@ -30,17 +188,38 @@ impl Config {
     }
 
     /// Load configuration from a list of paths, using the first one that exists.
-    fn load_from_paths(paths: &[PathBuf]) -> Self {
+    fn load_from_paths(paths: &[PathBuf]) -> Result<Self> {
         for path in paths {
             if path.exists() {
-                if let Ok(contents) = std::fs::read_to_string(path) {
-                    if let Ok(config) = toml::from_str(&contents) {
-                        return config;
-                    }
+                let contents = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read config file {}", path.display()))?;
+                let mut config = Self::parse(&contents)
+                    .with_context(|| format!("Invalid config file {}", path.display()))?;
+                if let Some(glossary) = &mut config.glossary
+                    && let Some(dir) = path.parent()
+                {
+                    *glossary = dir.join(&*glossary);
                 }
+                return Ok(config);
             }
         }
-        Self::default()
+        Ok(Self::default())
+    }
+
+    /// Parse and validate configuration from TOML text.
+    fn parse(contents: &str) -> Result<Self> {
+        let config: Self = toml::from_str(contents)?;
+        config.ranking.validate()?;
+        config.eval.validate()?;
+        Ok(config)
+    }
+
+    /// Load the configured glossary, or an empty one when none is set.
+    pub fn load_glossary(&self) -> Result<Glossary> {
+        match &self.glossary {
+            Some(path) => Glossary::load(path),
+            None => Ok(Glossary::default()),
+        }
     }
 
     /// Get the default top value (3 if not configured).
@@ -88,8 +267,66 @@ mod tests {
             default_top: 5,
             clip_length: 100,
             skills_dir: Some(PathBuf::from("/custom/path")),
+            ..Default::default()
         };
         assert_eq!(config.get_default_top(), 5);
         assert_eq!(config.get_clip_length(), 100);
     }
+
+    #[test]
+    fn test_ranking_defaults_match_builtin_weights() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.ranking.name_weight, 8);
+        assert_eq!(config.ranking.name_similarity_gate, 0.92);
+        assert_eq!(config.ranking.summary_similarity_gate, 0.94);
+    }
+
+    #[test]
+    fn test_ranking_table_overrides_selected_values() {
+        let config = Config::parse("[ranking]\ntag_weight = 9\n").unwrap();
+        assert_eq!(config.ranking.tag_weight, 9);
+        assert_eq!(config.ranking.summary_weight, 5);
+    }
+
+    #[test]
+    fn test_invalid_ranking_values_are_errors() {
+        assert!(Config::parse("[ranking]\nname_similarity_gate = 1.5\n").is_err());
+        assert!(Config::parse("[ranking]\ntag_weight = -1\n").is_err());
+        assert!(Config::parse("[ranking]\ntag_wieght = 2\n").is_err());
+    }
+
+    #[test]
+    fn test_eval_thresholds_parse_and_validate() {
+        let config = Config::parse("[eval]\nmin_precision_at_1 = 0.9\n").unwrap();
+        assert_eq!(config.eval.min_precision_at_1, 0.9);
+        assert_eq!(config.eval.min_mrr, 0.0);
+        assert!(Config::parse("[eval]\nmin_mrr = 2.0\n").is_err());
+    }
+
+    #[test]
+    fn test_synonyms_table_parses() {
+        let config: Config = toml::from_str(
+            r#"
+            [synonyms]
+            ios = ["iphone"]
+            perf = ["performance", "speed"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.synonyms["ios"], vec!["iphone"]);
+        assert_eq!(config.synonyms["perf"].len(), 2);
+    }
+
+    #[test]
+    fn test_glossary_path_is_relative_to_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "glossary = \"glossary.toml\"\n").unwrap();
+        std::fs::write(dir.path().join("glossary.toml"), "correo = [\"email\"]\n").unwrap();
+
+        let config = Config::load_from_paths(&[path]).unwrap();
+        assert_eq!(config.glossary, Some(dir.path().join("glossary.toml")));
+        let glossary = config.load_glossary().unwrap();
+        assert_eq!(glossary.translate("correo").trim(), "email");
+    }
 }
@@ -0,0 +1,93 @@
+//! Project context detection for `pick --context`.
+//!
+//! Skills declare `applies_to:` file names or globs (`Cargo.toml`,
+//! `*.xcodeproj`, `firebase.json`); those present in the project directory
+//! boost the skills that declared them.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use glob::{glob, Pattern};
+
+use crate::skill::Skill;
+
+/// Markers from skills' `applies_to` lists found in a project directory.
+#[derive(Debug, Clone, Default)]
+pub struct RepoContext {
+    /// Directory that was inspected, if any.
+    pub dir: Option<PathBuf>,
+    /// `applies_to` patterns that matched at least one path, sorted.
+    pub markers: BTreeSet<String>,
+}
+
+impl RepoContext {
+    /// Check every `applies_to` pattern declared by `skills` against `dir`.
+    /// Patterns are globs relative to the directory, so `*.xcodeproj` looks
+    /// only at the top level while `**/Package.swift` searches nested folders.
+    pub fn detect(dir: &Path, skills: &[Skill]) -> Result<Self> {
+        if !dir.is_dir() {
+            bail!("Context directory {} does not exist", dir.display());
+        }
+        let patterns: BTreeSet<&str> = skills
+            .iter()
+            .flat_map(|s| s.applies_to.iter().map(String::as_str))
+            .collect();
+
+        let root = PathBuf::from(Pattern::escape(&dir.to_string_lossy()));
+        let mut markers = BTreeSet::new();
+        for pattern in patterns {
+            let full = root.join(pattern);
+            let mut matches = glob(&full.to_string_lossy())
+                .with_context(|| format!("Invalid applies_to pattern '{}'", pattern))?;
+            if matches.any(|entry| entry.is_ok()) {
+                markers.insert(pattern.to_string());
+            }
+        }
+        Ok(Self {
+            dir: Some(dir.to_path_buf()),
+            markers,
+        })
+    }
+
+    /// Number of the skill's `applies_to` patterns present in the project.
+    pub fn hits(&self, skill: &Skill) -> usize {
+        skill
+            .applies_to
+            .iter()
+            .filter(|p| self.markers.contains(p.as_str()))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_files_and_globbed_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        std::fs::create_dir(dir.path().join("App.xcodeproj")).unwrap();
+        let skills = vec![
+            Skill::for_test("rust").applies_to(&["Cargo.toml"]),
+            Skill::for_test("ios").applies_to(&["*.xcodeproj", "Package.swift"]),
+            Skill::for_test("firebase").applies_to(&["firebase.json"]),
+        ];
+
+        let context = RepoContext::detect(dir.path(), &skills).unwrap();
+        assert_eq!(
+            context.markers.iter().collect::<Vec<_>>(),
+            vec!["*.xcodeproj", "Cargo.toml"]
+        );
+        assert_eq!(context.hits(&skills[0]), 1);
+        assert_eq!(context.hits(&skills[1]), 1);
+        assert_eq!(context.hits(&skills[2]), 0);
+    }
+
+    #[test]
+    fn test_default_context_boosts_nothing() {
+        let context = RepoContext::default();
+        assert_eq!(context.hits(&Skill::for_test("rust").applies_to(&["Cargo.toml"])), 0);
+    }
+}
@@ -0,0 +1,141 @@
+//! Query derivation from a git diff (`pick --from-diff`).
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+/// Language names for common source extensions, so a diff touching `.rs`
+/// files routes like a query mentioning "rust".
+const EXTENSION_LANGUAGES: &[(&str, &str)] = &[
+    ("rs", "rust"),
+    ("swift", "swift"),
+    ("ts", "typescript"),
+    ("tsx", "typescript react"),
+    ("js", "javascript"),
+    ("jsx", "javascript react"),
+    ("py", "python"),
+    ("kt", "kotlin"),
+    ("go", "go"),
+    ("css", "css"),
+    ("html", "html"),
+    ("md", "markdown docs"),
+];
+
+/// Keywords and conventional directory names that say nothing about the task.
+const NOISE: &[&str] = &[
+    "func", "let", "var", "pub", "impl", "struct", "enum", "class", "return", "import", "use",
+    "self", "Self", "const", "static", "true", "false", "nil", "null", "None", "Some", "new",
+    "private", "public", "internal", "final", "override", "def", "function", "export", "from",
+    "src", "Sources", "lib", "app", "main", "index", "mod",
+];
+
+/// Read the staged diff, or the diff against `rev`, from the git repository
+/// containing the current directory.
+pub fn git_diff(rev: Option<&str>) -> Result<String> {
+    // git would read a leading dash as an option, e.g. `--output=FILE`.
+    if let Some(rev) = rev
+        && rev.starts_with('-')
+    {
+        bail!("Invalid revision '{}': revisions cannot start with '-'", rev);
+    }
+    let mut cmd = Command::new("git");
+    cmd.args(["diff", "--no-color", "--unified=0"]);
+    match rev {
+        Some(rev) => cmd.arg(rev),
+        None => cmd.arg("--cached"),
+    };
+    // A revision that also names a file must not be read as a path.
+    cmd.arg("--");
+    let output = cmd.output().context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git diff failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let diff = String::from_utf8_lossy(&output.stdout).into_owned();
+    if diff.trim().is_empty() {
+        match rev {
+            Some(rev) => bail!("No changes against {}", rev),
+            None => bail!("No staged changes; stage files or pass a revision to --from-diff"),
+        }
+    }
+    Ok(diff)
+}
+
+/// Build a task description from a unified diff: the languages of changed
+/// files, their path components and the identifiers on added lines, each
+/// listed once in order of appearance and without language keywords.
+pub fn diff_query(diff: &str) -> String {
+    let mut terms: Vec<String> = Vec::new();
+    let mut push = |term: &str| {
+        if !NOISE.contains(&term) && !terms.iter().any(|t| t == term) {
+            terms.push(term.to_string());
+        }
+    };
+
+    for line in diff.lines() {
+        if let Some(path) = line.strip_prefix("+++ b/") {
+            let path = Path::new(path);
+            if let Some(language) = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(|ext| EXTENSION_LANGUAGES.iter().find(|(e, _)| *e == ext))
+                .map(|(_, language)| *language)
+            {
+                push(language);
+            }
+            let stem = path.with_extension("");
+            for part in stem.iter().filter_map(|p| p.to_str()) {
+                push(part);
+            }
+        } else if let Some(added) = line.strip_prefix('+')
+            && !line.starts_with("+++")
+        {
+            for ident in identifiers(added) {
+                push(ident);
+            }
+        }
+    }
+    terms.join(" ")
+}
+
+/// Identifier-like words (a letter followed by letters, digits or `_`) of
+/// at least three characters.
+fn identifiers(code: &str) -> impl Iterator<Item = &str> {
+    code.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| w.chars().next().is_some_and(char::is_alphabetic))
+        .filter(|w| w.chars().count() >= 3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/Sources/Feed/FeedLoader.swift b/Sources/Feed/FeedLoader.swift
+--- a/Sources/Feed/FeedLoader.swift
++++ b/Sources/Feed/FeedLoader.swift
+@@ -10,0 +11,3 @@
++        Task { @MainActor in
++            await loader.refresh()
++        }
+-        old_call(42)
+";
+
+    #[test]
+    fn test_query_lists_language_paths_and_added_identifiers() {
+        assert_eq!(
+            diff_query(DIFF),
+            "swift Feed FeedLoader Task MainActor await loader refresh"
+        );
+    }
+
+    #[test]
+    fn test_removed_lines_and_short_words_are_ignored() {
+        let query = diff_query(DIFF);
+        assert!(!query.contains("old_call"));
+        assert!(!query.split(' ').any(|w| w == "in"));
+    }
+}
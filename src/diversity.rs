@@ -0,0 +1,128 @@
+//! Maximal marginal relevance (MMR) re-ranking for diverse top-k results.
+
+use std::collections::HashMap;
+
+use crate::bm25::Bm25Index;
+use crate::matching::SkillSignals;
+use crate::skill::Skill;
+
+/// Most candidates considered for re-ranking; the rest keep their order.
+const MMR_POOL: usize = 20;
+
+/// IDF-weighted, L2-normalized term vector of a skill's routing fields and body.
+fn content_vector<'a>(skill: &'a Skill, index: &Bm25Index) -> HashMap<&'a str, f64> {
+    let mut vector: HashMap<&str, f64> = HashMap::new();
+    for token in skill
+        .name_tokens
+        .iter()
+        .chain(&skill.summary_tokens)
+        .chain(&skill.tag_tokens)
+        .chain(&skill.alias_tokens)
+        .chain(&skill.body_tokens)
+    {
+        *vector.entry(token.as_str()).or_insert(0.0) += 1.0;
+    }
+    for (term, weight) in vector.iter_mut() {
+        *weight = (1.0 + weight.ln()) * index.idf(term);
+    }
+    let norm = vector.values().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        vector.values_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+/// Cosine similarity of two normalized vectors.
+fn cosine(a: &HashMap<&str, f64>, b: &HashMap<&str, f64>) -> f64 {
+    a.iter().filter_map(|(t, x)| b.get(t).map(|y| x * y)).sum()
+}
+
+/// Reorder the first `k` results so each balances relevance (score relative
+/// to the best) against content similarity to the results above it.
+/// `diversity` is the similarity share: 0 keeps the ranking, 1 maximizes
+/// spread. Scores are untouched and skills scoring 0 are never promoted.
+pub fn mmr_rerank(
+    ranked: &mut Vec<(usize, &Skill, SkillSignals)>,
+    index: &Bm25Index,
+    k: usize,
+    diversity: f64,
+) {
+    let pool_len = ranked
+        .iter()
+        .take(MMR_POOL)
+        .take_while(|(score, _, _)| *score > 0)
+        .count();
+    if diversity <= 0.0 || pool_len < 2 || k < 2 {
+        return;
+    }
+
+    let pool = &ranked[..pool_len];
+    let vectors: Vec<_> = pool.iter().map(|(_, s, _)| content_vector(s, index)).collect();
+    let best = pool[0].0 as f64;
+
+    let mut chosen: Vec<usize> = Vec::new();
+    let mut remaining: Vec<usize> = (0..pool.len()).collect();
+    while chosen.len() < k.min(pool.len()) {
+        let Some((pos, _)) = remaining
+            .iter()
+            .enumerate()
+            .map(|(pos, &i)| {
+                let relevance = pool[i].0 as f64 / best;
+                let redundancy = chosen
+                    .iter()
+                    .map(|&j| cosine(&vectors[i], &vectors[j]))
+                    .fold(0.0, f64::max);
+                (pos, (1.0 - diversity) * relevance - diversity * redundancy)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+        else {
+            break;
+        };
+        chosen.push(remaining.remove(pos));
+    }
+
+    // Chosen results first, then the rest of the pool in score order.
+    let reordered: Vec<_> = chosen
+        .into_iter()
+        .chain(remaining)
+        .map(|i| pool[i].clone())
+        .collect();
+    ranked.splice(..pool_len, reordered);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(ranked: &[(usize, &Skill, SkillSignals)]) -> Vec<String> {
+        ranked.iter().map(|(_, s, _)| s.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_near_duplicates_give_way_to_distinct_alternatives() {
+        let skills = vec![
+            Skill::for_test("ios-ux").body("iphone screens navigation layout"),
+            Skill::for_test("ios-design").body("iphone screens navigation layout"),
+            Skill::for_test("testing").body("xctest unit tests coverage"),
+            Skill::for_test("unrelated").body("cooking"),
+        ];
+        let index = Bm25Index::build(&skills);
+        let ranked = vec![
+            (40, &skills[0], SkillSignals::default()),
+            (38, &skills[1], SkillSignals::default()),
+            (30, &skills[2], SkillSignals::default()),
+            (0, &skills[3], SkillSignals::default()),
+        ];
+
+        let mut plain = ranked.clone();
+        mmr_rerank(&mut plain, &index, 2, 0.0);
+        assert_eq!(names(&plain), names(&ranked));
+
+        let mut diverse = ranked.clone();
+        mmr_rerank(&mut diverse, &index, 2, 0.5);
+        assert_eq!(
+            names(&diverse),
+            vec!["ios-ux", "testing", "ios-design", "unrelated"]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Routing evaluation against a golden set of queries (`codex-skills eval`).
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// A golden query and the skill names that count as a correct pick.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoldenCase {
+    pub query: String,
+    pub expected: Vec<String>,
+}
+
+/// Read golden cases from a `.jsonl` file (one object per line) or a YAML
+/// list (any other extension; JSON arrays parse as YAML too).
+pub fn load_golden(path: &Path) -> Result<Vec<GoldenCase>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read golden file {}", path.display()))?;
+    if path.extension().is_some_and(|ext| ext == "jsonl") {
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(n, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Invalid golden entry at {}:{}", path.display(), n + 1))
+            })
+            .collect()
+    } else {
+        serde_yaml::from_str(&contents)
+            .with_context(|| format!("Invalid golden file {}", path.display()))
+    }
+}
+
+/// Aggregate routing metrics over all golden cases.
+#[derive(Debug, Clone, Default)]
+pub struct EvalReport {
+    pub cases: usize,
+    pub k: usize,
+    /// Share of queries whose top result is expected.
+    pub precision_at_1: f64,
+    /// Mean share of the top `k` slots holding expected skills, out of the
+    /// slots they could fill (`min(k, expected.len())`).
+    pub precision_at_k: f64,
+    /// Mean reciprocal rank of the first expected skill (0 when absent).
+    pub mrr: f64,
+    /// (expected, picked instead) pairs with counts, most frequent first.
+    pub confusions: Vec<(String, String, usize)>,
+}
+
+impl EvalReport {
+    /// Score ranked skill names (best first) against each case's expectations.
+    pub fn new(results: &[(&GoldenCase, Vec<&str>)], k: usize) -> Self {
+        let mut report = Self {
+            cases: results.len(),
+            k,
+            ..Default::default()
+        };
+        if results.is_empty() {
+            return report;
+        }
+
+        let mut confusions: HashMap<(String, String), usize> = HashMap::new();
+        for (case, ranked) in results {
+            let is_expected = |name: &str| case.expected.iter().any(|e| e == name);
+
+            match ranked.first() {
+                Some(top) if is_expected(top) => report.precision_at_1 += 1.0,
+                Some(top) => {
+                    if let Some(wanted) = case.expected.first() {
+                        *confusions
+                            .entry((wanted.clone(), top.to_string()))
+                            .or_insert(0) += 1;
+                    }
+                }
+                None => {}
+            }
+
+            let slots = k.min(case.expected.len());
+            if slots > 0 {
+                let hits = ranked.iter().take(k).filter(|n| is_expected(n)).count();
+                report.precision_at_k += hits as f64 / slots as f64;
+            }
+
+            if let Some(rank) = ranked.iter().position(|n| is_expected(n)) {
+                report.mrr += 1.0 / (rank + 1) as f64;
+            }
+        }
+
+        let n = results.len() as f64;
+        report.precision_at_1 /= n;
+        report.precision_at_k /= n;
+        report.mrr /= n;
+
+        report.confusions = confusions
+            .into_iter()
+            .map(|((wanted, got), count)| (wanted, got, count))
+            .collect();
+        report
+            .confusions
+            .sort_by(|a, b| b.2.cmp(&a.2).then_with(|| (&a.0, &a.1).cmp(&(&b.0, &b.1))));
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(query: &str, expected: &[&str]) -> GoldenCase {
+        GoldenCase {
+            query: query.to_string(),
+            expected: expected.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_metrics_over_hits_and_misses() {
+        let hit = case("a", &["alpha"]);
+        let second = case("b", &["beta"]);
+        let miss = case("c", &["gamma"]);
+        let results = vec![
+            (&hit, vec!["alpha", "beta", "gamma"]),
+            (&second, vec!["alpha", "beta", "gamma"]),
+            (&miss, vec!["alpha", "beta", "delta"]),
+        ];
+        let report = EvalReport::new(&results, 2);
+
+        assert_eq!(report.cases, 3);
+        assert!((report.precision_at_1 - 1.0 / 3.0).abs() < 1e-9);
+        assert!((report.precision_at_k - 2.0 / 3.0).abs() < 1e-9);
+        assert!((report.mrr - (1.0 + 0.5) / 3.0).abs() < 1e-9);
+        assert_eq!(
+            report.confusions,
+            vec![
+                ("beta".to_string(), "alpha".to_string(), 1),
+                ("gamma".to_string(), "alpha".to_string(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_precision_at_k_uses_fillable_slots() {
+        let multi = case("x", &["alpha", "beta"]);
+        let results = vec![(&multi, vec!["alpha", "gamma", "beta"])];
+        assert!((EvalReport::new(&results, 3).precision_at_k - 1.0).abs() < 1e-9);
+        assert!((EvalReport::new(&results, 2).precision_at_k - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_empty_golden_set_reports_zero() {
+        let report = EvalReport::new(&[], 3);
+        assert_eq!(report.cases, 0);
+        assert_eq!(report.mrr, 0.0);
+    }
+}
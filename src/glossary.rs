@@ -0,0 +1,172 @@
+//! Bilingual glossary that maps non-English query terms to English ones.
+//!
+//! Skills are written in English, so a query such as "configurar
+//! notificaciones push" or "プッシュ通知を設定" only reaches them once its
+//! terms are translated. The glossary is a TOML table of
+//! `term = ["english", "terms"]` entries named by `glossary` in the config.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::skill::is_cjk;
+
+/// Term translations applied to queries before tokenization.
+#[derive(Debug, Clone, Default)]
+pub struct Glossary {
+    /// Lowercased terms as characters with their English replacement,
+    /// longest first so "プッシュ通知" wins over "通知".
+    entries: Vec<(Vec<char>, String)>,
+}
+
+impl Glossary {
+    /// Build a glossary from raw entries (term -> English terms).
+    pub fn from_table(table: &HashMap<String, Vec<String>>) -> Self {
+        let mut entries: Vec<(Vec<char>, String)> = table
+            .iter()
+            .filter(|(term, english)| !term.trim().is_empty() && !english.is_empty())
+            .map(|(term, english)| (lowercase_chars(term.trim()), english.join(" ")))
+            .collect();
+        entries.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        Self { entries }
+    }
+
+    /// Read a glossary TOML file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read glossary {}", path.display()))?;
+        let table: HashMap<String, Vec<String>> = toml::from_str(&contents)
+            .with_context(|| format!("Invalid glossary {}", path.display()))?;
+        Ok(Self::from_table(&table))
+    }
+
+    /// Replace glossary terms in `text` with their English translations,
+    /// matching case-insensitively and longest term first. CJK terms match
+    /// anywhere, since those scripts do not separate words with spaces;
+    /// other terms must stand as whole words.
+    pub fn translate(&self, text: &str) -> String {
+        if self.entries.is_empty() {
+            return text.to_string();
+        }
+        // Lowercasing may turn one character into several (e.g. 'İ'), so
+        // `source` maps each lowercased character back to its original.
+        let chars: Vec<char> = text.chars().collect();
+        let mut lower = Vec::with_capacity(chars.len());
+        let mut source = Vec::with_capacity(chars.len());
+        for (n, c) in chars.iter().enumerate() {
+            for l in c.to_lowercase() {
+                lower.push(l);
+                source.push(n);
+            }
+        }
+        let starts_char = |i: usize| i == 0 || i == lower.len() || source[i] != source[i - 1];
+        let is_word = |c: &char| c.is_alphanumeric() && !is_cjk(*c);
+
+        let mut out = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < lower.len() {
+            let matched = self.entries.iter().find(|(term, _)| {
+                let end = i + term.len();
+                if end > lower.len()
+                    || lower[i..end] != term[..]
+                    || !starts_char(i)
+                    || !starts_char(end)
+                {
+                    return false;
+                }
+                let cjk = term.iter().all(|c| is_cjk(*c));
+                let inside_word =
+                    (i > 0 && is_word(&lower[i - 1])) || lower.get(end).is_some_and(is_word);
+                cjk || !inside_word
+            });
+            match matched {
+                Some((term, english)) => {
+                    out.push(' ');
+                    out.push_str(english);
+                    out.push(' ');
+                    i += term.len();
+                }
+                None => {
+                    if starts_char(i) {
+                        out.push(chars[source[i]]);
+                    }
+                    i += 1;
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Lowercase `text` one character at a time, so queries and terms fold the
+/// same way regardless of context (unlike `str::to_lowercase` and 'Σ').
+fn lowercase_chars(text: &str) -> Vec<char> {
+    text.chars().flat_map(char::to_lowercase).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary(entries: &[(&str, &[&str])]) -> Glossary {
+        let table = entries
+            .iter()
+            .map(|(term, english)| {
+                (
+                    term.to_string(),
+                    english.iter().map(|e| e.to_string()).collect(),
+                )
+            })
+            .collect();
+        Glossary::from_table(&table)
+    }
+
+    #[test]
+    fn test_translates_whole_words_case_insensitively() {
+        let g = glossary(&[
+            ("notificaciones", &["notifications"]),
+            ("configurar", &["configure", "set up"]),
+        ]);
+        assert_eq!(
+            g.translate("Configurar notificaciones push")
+                .split_whitespace()
+                .collect::<Vec<_>>(),
+            vec!["configure", "set", "up", "notifications", "push"]
+        );
+        assert_eq!(g.translate("preconfigurar"), "preconfigurar");
+    }
+
+    #[test]
+    fn test_cjk_terms_match_inside_text_longest_first() {
+        let g = glossary(&[
+            ("プッシュ通知", &["push notifications"]),
+            ("通知", &["notice"]),
+            ("設定", &["configure"]),
+        ]);
+        assert_eq!(
+            g.translate("プッシュ通知を設定")
+                .split_whitespace()
+                .collect::<Vec<_>>(),
+            vec!["push", "notifications", "を", "configure"]
+        );
+    }
+
+    #[test]
+    fn test_terms_with_multi_char_lowercase_match() {
+        let g = glossary(&[("İptal", &["cancel"])]);
+        assert_eq!(
+            g.translate("İPTAL et")
+                .split_whitespace()
+                .collect::<Vec<_>>(),
+            vec!["cancel", "et"]
+        );
+        assert_eq!(g.translate("İzmir"), "İzmir");
+    }
+
+    #[test]
+    fn test_empty_glossary_leaves_text_alone() {
+        assert_eq!(Glossary::default().translate("Hola"), "Hola");
+    }
+}
@@ -0,0 +1,151 @@
+//! Conversation history for `pick --history`.
+//!
+//! Follow-up turns ("now add tests for it") rarely name their subject, so
+//! tokens from earlier turns are blended into the query with weights that
+//! decay by recency, and the skill picked last can be kept sticky.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::skill::{find_skill_exact, normalized_tokens, Skill};
+
+/// One earlier turn: what was asked and, optionally, the skill selected.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Turn {
+    pub query: String,
+    #[serde(default)]
+    pub skill: Option<String>,
+}
+
+/// Earlier turns of a conversation, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub turns: Vec<Turn>,
+}
+
+impl History {
+    /// Read a history file with one turn per line, oldest first. Lines
+    /// starting with `{` are JSON objects such as
+    /// `{"query": "...", "skill": "..."}`; other lines are plain queries.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read history file {}", path.display()))?;
+        let mut turns = Vec::new();
+        for (n, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let turn = if line.starts_with('{') {
+                serde_json::from_str(line).with_context(|| {
+                    format!("Invalid history entry at {}:{}", path.display(), n + 1)
+                })?
+            } else {
+                Turn {
+                    query: line.to_string(),
+                    skill: None,
+                }
+            };
+            turns.push(turn);
+        }
+        Ok(Self { turns })
+    }
+
+    /// Replace each turn's skill with the canonical name of the skill whose
+    /// name or alias it matches exactly (case-insensitive). Skills that match
+    /// nothing are cleared and their names returned, so a typo never makes
+    /// an unrelated skill sticky.
+    pub fn resolve_skills(&mut self, skills: &[Skill]) -> Vec<String> {
+        let mut unknown = Vec::new();
+        for turn in &mut self.turns {
+            let Some(name) = turn.skill.take() else {
+                continue;
+            };
+            match find_skill_exact(skills, &name) {
+                Some(skill) => turn.skill = Some(skill.name.clone()),
+                None => unknown.push(name),
+            }
+        }
+        unknown
+    }
+
+    /// Tokens of all turns weighted by `decay` raised to their age: the last
+    /// turn has age 1, the one before it age 2, and so on. A token used in
+    /// several turns keeps its most recent (highest) weight. Sorted by
+    /// descending weight, then token.
+    pub fn weighted_tokens(&self, decay: f64) -> Vec<(String, f64)> {
+        let mut weights: HashMap<String, f64> = HashMap::new();
+        for (age, turn) in self.turns.iter().rev().enumerate() {
+            let weight = decay.powi(age as i32 + 1);
+            if weight <= 0.0 {
+                break;
+            }
+            for token in normalized_tokens(&turn.query) {
+                let entry = weights.entry(token).or_insert(0.0);
+                *entry = entry.max(weight);
+            }
+        }
+        let mut tokens: Vec<(String, f64)> = weights.into_iter().collect();
+        tokens.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        tokens
+    }
+
+    /// The skill selected in the most recent turn that recorded one.
+    pub fn previous_skill(&self) -> Option<&str> {
+        self.turns.iter().rev().find_map(|t| t.skill.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(query: &str, skill: Option<&str>) -> Turn {
+        Turn {
+            query: query.to_string(),
+            skill: skill.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_recent_turns_weigh_more() {
+        let history = History {
+            turns: vec![turn("storekit purchases", None), turn("swiftui paywall", None)],
+        };
+        let weights: HashMap<String, f64> = history.weighted_tokens(0.5).into_iter().collect();
+        assert_eq!(weights["paywal"], 0.5);
+        assert_eq!(weights["storekit"], 0.25);
+        assert!(history.weighted_tokens(0.0).is_empty());
+    }
+
+    #[test]
+    fn test_loads_plain_and_json_turns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        fs::write(
+            &path,
+            "design the paywall\n{\"query\": \"build it\", \"skill\": \"build-iphone-apps\"}\n\n{\"query\": \"polish\"}\n",
+        )
+        .unwrap();
+        let history = History::load(&path).unwrap();
+        assert_eq!(history.turns.len(), 3);
+        assert_eq!(history.turns[0].query, "design the paywall");
+        assert_eq!(history.previous_skill(), Some("build-iphone-apps"));
+    }
+
+    #[test]
+    fn test_skills_resolve_by_exact_name_or_alias() {
+        let skills = vec![Skill::for_test("Rust Best Practices Guide").aliases(&["rust"])];
+        let mut history = History {
+            turns: vec![turn("a", Some("Rust")), turn("b", Some("rust best")), turn("c", None)],
+        };
+        assert_eq!(history.resolve_skills(&skills), vec!["rust best".to_string()]);
+        assert_eq!(history.turns[0].skill.as_deref(), Some("Rust Best Practices Guide"));
+        assert_eq!(history.turns[1].skill, None);
+        assert_eq!(history.previous_skill(), Some("Rust Best Practices Guide"));
+    }
+}
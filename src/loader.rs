@@ -109,27 +109,13 @@ mod tests {
     #[test]
     fn test_dedupe_skills_removes_duplicates() {
         let mut skills = vec![
-            create_test_skill("test-skill"),
-            create_test_skill("Test-Skill"), // Same name, different case
-            create_test_skill("other-skill"),
+            Skill::for_test("test-skill").summary("Test summary").body("Test doc"),
+            Skill::for_test("Test-Skill").summary("Test summary").body("Test doc"), // Same name, different case
+            Skill::for_test("other-skill").summary("Test summary").body("Test doc"),
         ];
         dedupe_skills(&mut skills);
         assert_eq!(skills.len(), 2);
         assert_eq!(skills[0].name, "test-skill");
         assert_eq!(skills[1].name, "other-skill");
     }
-
-    fn create_test_skill(name: &str) -> Skill {
-        Skill {
-            name: name.to_string(),
-            summary: "Test summary".to_string(),
-            keywords: vec![],
-            doc: "Test doc".to_string(),
-            extra_docs: vec![],
-            name_tokens: vec![],
-            summary_tokens: vec![],
-            tag_tokens: vec![],
-            body_tokens: vec![],
-        }
-    }
 }
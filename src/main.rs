@@ -1,20 +1,44 @@
 //! codex-skills: Route tasks to the right skill playbook.
 
+mod bm25;
+mod classifier;
 mod commands;
 mod config;
+mod context;
+mod diff;
+mod diversity;
+mod eval;
+mod glossary;
+mod history;
 mod loader;
+mod markdown;
 mod matching;
+#[cfg(feature = "semantic")]
+mod semantic;
 mod skill;
+mod spelling;
+mod synonyms;
+mod syntax;
 
-use std::path::PathBuf;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use clap::{Parser, Subcommand};
 use include_dir::{Dir, include_dir};
 
-use commands::{cmd_instructions, cmd_list, cmd_pick, cmd_show};
+use classifier::{Classifier, model_path};
+use commands::{
+    PickMode, PickOptions, cmd_eval, cmd_instructions, cmd_list, cmd_pick, cmd_pick_batch,
+    cmd_show, cmd_train,
+};
 use config::Config;
+use context::RepoContext;
+use history::History;
 use loader::{load_skills_with_fallback, materialize_skills};
+use synonyms::Synonyms;
+use syntax::{QueryField, QuerySyntax};
 
 #[derive(Parser, Debug)]
 #[command(name = "codex-skills", about = "Route tasks to the right skill playbook.")]
@@ -47,14 +71,84 @@ enum Command {
 
     /// Suggest the best matching skills for a task description
     Pick {
-        /// Free-form task description to match against skills
-        query: String,
+        /// Free-form task description to match against skills (`-` reads stdin);
+        /// supports "phrases", -term, name:, tag: and body: prefixes
+        #[arg(
+            required_unless_present_any = ["batch", "query_file", "from_diff"],
+            allow_hyphen_values = true
+        )]
+        query: Option<String>,
+        /// Read the task description from a file, e.g. an issue or PR body
+        #[arg(long, value_name = "FILE", conflicts_with = "query")]
+        query_file: Option<PathBuf>,
+        /// Derive the query from the staged git diff, or the diff against REV
+        /// (`--from-diff=REV`)
+        #[arg(
+            long,
+            value_name = "REV",
+            num_args = 0..=1,
+            require_equals = true,
+            conflicts_with_all = ["query", "query_file"]
+        )]
+        from_diff: Option<Option<String>>,
+        /// Rank every query in a file (`-` for stdin), one per line or as JSONL
+        /// objects with `query` and optional `id`, and print JSONL results
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["query", "query_file", "from_diff", "show", "focused", "explain"]
+        )]
+        batch: Option<PathBuf>,
         /// Number of candidates to show
         #[arg(short, long, default_value_t = 3)]
         top: usize,
         /// Immediately print the full playbook for the top result
         #[arg(long)]
         show: bool,
+        /// Print only the best-matching document of the top result (implies --show)
+        #[arg(long)]
+        focused: bool,
+        /// Print per-token field matches and weighted contributions for each result
+        #[arg(long)]
+        explain: bool,
+        /// Split the query into intents at conjunctions and sentence ends and
+        /// pick skills for each part
+        #[arg(long, conflicts_with_all = ["batch", "show", "focused", "explain"])]
+        split: bool,
+        /// Trade relevance for variety (0-1) among the top results; overrides
+        /// `ranking.diversity` from config
+        #[arg(long, value_name = "0-1")]
+        diversity: Option<f64>,
+        /// Boost skills whose `applies_to` markers exist in this project
+        /// directory (`--context=DIR`; defaults to the current directory)
+        #[arg(
+            long,
+            value_name = "DIR",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "."
+        )]
+        context: Option<PathBuf>,
+        /// Earlier conversation turns, oldest first: plain lines or JSONL
+        /// `{"query": ..., "skill": ...}`; blended into the query by recency
+        #[arg(long, value_name = "FILE", conflicts_with = "batch")]
+        history: Option<PathBuf>,
+        /// Ranking strategy (semantic requires the `semantic` cargo feature)
+        #[arg(long, value_enum, default_value_t = PickMode::Lexical)]
+        mode: PickMode,
+    },
+
+    /// Train the query classifier from skill `examples:` and save it next to
+    /// the skills directory
+    Train,
+
+    /// Score routing against a golden file of queries and expected skills
+    Eval {
+        /// YAML list or JSONL file of `{query, expected: [skill names]}`
+        golden: PathBuf,
+        /// Cutoff rank for precision@k
+        #[arg(short, long, default_value_t = 3)]
+        k: usize,
     },
 
     /// Open a specific skill by name
@@ -85,7 +179,9 @@ enum Command {
 
     /// Search within skill content
     Search {
-        /// Text to search for in skill bodies
+        /// Text to search for in skill bodies; supports "phrases", -term,
+        /// name:, tag: and body: prefixes
+        #[arg(allow_hyphen_values = true)]
         query: String,
         /// Show context around matches
         #[arg(long, short, default_value_t = 2)]
@@ -98,10 +194,10 @@ static EMBEDDED_SKILLS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/skills");
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    let config = Config::load();
+    let config = Config::load()?;
 
     // Use config for skills_dir if not overridden on command line
-    let skills_dir = if cli.skills_dir == PathBuf::from("skills") {
+    let skills_dir = if cli.skills_dir.as_path() == Path::new("skills") {
         config.skills_dir.clone().unwrap_or(cli.skills_dir)
     } else {
         cli.skills_dir
@@ -140,14 +236,121 @@ fn main() -> Result<()> {
             };
             cmd_list(&skills, brief, verbose, json, effective_clip);
         }
-        Command::Pick { query, top, show } => {
+        Command::Pick {
+            query,
+            query_file,
+            from_diff,
+            batch,
+            top,
+            show,
+            focused,
+            explain,
+            split,
+            diversity,
+            context,
+            history,
+            mode,
+        } => {
+            if mode == PickMode::Semantic && !cfg!(feature = "semantic") {
+                bail!("--mode semantic requires codex-skills built with `--features semantic`");
+            }
             // Use config top value if default was used
             let effective_top = if top == 3 {
                 config.get_default_top()
             } else {
                 top
             };
-            cmd_pick(&skills, &query, effective_top, show);
+            let diversity = diversity.unwrap_or(config.ranking.diversity);
+            if !(0.0..=1.0).contains(&diversity) {
+                bail!("--diversity must be between 0 and 1 (got {})", diversity);
+            }
+            let glossary = config.load_glossary()?;
+            let synonyms = Synonyms::from_table(&config.synonyms);
+            // Context detection is opt-in: --context, or `detect_context` in config
+            let context_dir = context.or_else(|| config.detect_context.then(|| PathBuf::from(".")));
+            let context = match context_dir {
+                Some(dir) => RepoContext::detect(&dir, &skills)?,
+                None => RepoContext::default(),
+            };
+            let history = match history {
+                Some(path) => {
+                    let mut history = History::load(&path)?;
+                    for name in history.resolve_skills(&skills) {
+                        eprintln!(
+                            "Note: history skill '{}' is not a skill name or alias; ignoring it",
+                            name
+                        );
+                    }
+                    history
+                }
+                None => History::default(),
+            };
+            // Only a query typed on the command line uses the query syntax;
+            // issue bodies, diffs and batch lines are plain text.
+            let query_syntax = query.as_deref().is_some_and(|q| q != "-")
+                && query_file.is_none()
+                && from_diff.is_none()
+                && batch.is_none();
+            let options = PickOptions {
+                mode,
+                top: effective_top,
+                show,
+                focused,
+                explain,
+                context,
+                split,
+                diversity,
+                history,
+                query_syntax,
+            };
+            let model = load_current_model(&skills_dir, &skills)?;
+            if let Some(input) = batch {
+                cmd_pick_batch(
+                    &skills,
+                    &input,
+                    &options,
+                    &glossary,
+                    &synonyms,
+                    &config.ranking,
+                    model.as_ref(),
+                )?;
+            } else {
+                let query = match from_diff {
+                    Some(rev) => {
+                        let query = diff::diff_query(&diff::git_diff(rev.as_deref())?);
+                        println!("Query from diff: {}", commands::clip_summary(&query, 120));
+                        query
+                    }
+                    None => read_query(query, query_file)?,
+                };
+                cmd_pick(
+                    &skills,
+                    &query,
+                    &options,
+                    &glossary,
+                    &synonyms,
+                    &config.ranking,
+                    model.as_ref(),
+                );
+            }
+        }
+        Command::Eval { golden, k } => {
+            let cases = eval::load_golden(&golden)?;
+            let glossary = config.load_glossary()?;
+            let synonyms = Synonyms::from_table(&config.synonyms);
+            let model = load_current_model(&skills_dir, &skills)?;
+            cmd_eval(
+                &skills,
+                &cases,
+                k,
+                &glossary,
+                &synonyms,
+                model.as_ref(),
+                &config,
+            )?;
+        }
+        Command::Train => {
+            cmd_train(&skills, &skills_dir)?;
         }
         Command::Show { name } => {
             cmd_show(&skills, &name);
@@ -162,7 +365,7 @@ fn main() -> Result<()> {
             cmd_stats(&skills);
         }
         Command::Search { query, context } => {
-            cmd_search(&skills, &query, context);
+            cmd_search(&skills, &query, context)?;
         }
         Command::Init { .. } => unreachable!(),
     }
@@ -170,6 +373,36 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Resolve the pick query from the argument, stdin (`-`) or a file.
+fn read_query(query: Option<String>, query_file: Option<PathBuf>) -> Result<String> {
+    if let Some(path) = query_file {
+        return fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read query file {}", path.display()));
+    }
+    match query {
+        Some(query) if query == "-" => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .context("Failed to read query from stdin")?;
+            Ok(text)
+        }
+        Some(query) => Ok(query),
+        None => bail!("pick needs a query, `-` or --query-file"),
+    }
+}
+
+/// Load the trained classifier, only while it matches the loaded skills.
+fn load_current_model(skills_dir: &Path, skills: &[skill::Skill]) -> Result<Option<Classifier>> {
+    Ok(Classifier::load(&model_path(skills_dir))?.filter(|m| {
+        let current = m.is_current(skills);
+        if !current {
+            eprintln!("Note: skill model is out of date; run `codex-skills train` to refresh it");
+        }
+        current
+    }))
+}
+
 /// Execute the `validate` command.
 fn cmd_validate(skills: &[skill::Skill], strict: bool) {
     let mut errors = 0;
@@ -206,6 +439,13 @@ fn cmd_validate(skills: &[skill::Skill], strict: bool) {
             ));
         }
 
+        // Check negative triggers don't contradict tags
+        for trigger in &skill.avoid_when {
+            if skill.keywords.iter().any(|k| k.eq_ignore_ascii_case(trigger)) {
+                skill_warnings.push(format!("avoid_when entry '{}' is also a tag", trigger));
+            }
+        }
+
         // Check body content
         if skill.doc.is_empty() {
             skill_errors.push("Empty skill body".to_string());
@@ -287,16 +527,53 @@ fn cmd_stats(skills: &[skill::Skill]) {
 }
 
 /// Execute the `search` command.
-fn cmd_search(skills: &[skill::Skill], query: &str, context_lines: usize) {
-    let query_lower = query.to_lowercase();
+/// Lines must contain the free text and every quoted phrase and `body:`
+/// term; exclusions and `name:`/`tag:` terms filter whole skills.
+fn cmd_search(skills: &[skill::Skill], query: &str, context_lines: usize) -> Result<()> {
+    let syntax = QuerySyntax::parse(query);
+    let mut needles: Vec<String> = Vec::new();
+    if !syntax.free.is_empty() {
+        needles.push(syntax.free.join(" ").to_lowercase());
+    }
+    needles.extend(syntax.phrases.iter().map(|p| p.text.clone()));
+    needles.extend(
+        syntax
+            .scoped
+            .iter()
+            .filter(|(field, _)| *field == QueryField::Body)
+            .map(|(_, term)| term.text.clone()),
+    );
+    if needles.is_empty() {
+        bail!(
+            "Nothing to search for in '{}'; add text, a quoted phrase or a body: term",
+            query
+        );
+    }
+    // Phrases and body terms are matched per line, so only skill-level
+    // constraints decide which skills are searched.
+    let filters = QuerySyntax {
+        phrases: Vec::new(),
+        scoped: syntax
+            .scoped
+            .iter()
+            .filter(|(field, _)| *field != QueryField::Body)
+            .cloned()
+            .collect(),
+        ..syntax.clone()
+    };
+    let line_matches = |line: &str| {
+        let line = line.to_lowercase();
+        needles.iter().all(|n| line.contains(n.as_str()))
+    };
     let mut total_matches = 0;
+    let mut matched_skills = 0;
 
-    for skill in skills {
+    for skill in skills.iter().filter(|s| filters.admits(s)) {
         let mut skill_matches = Vec::new();
 
         // Search in main doc
         for (line_num, line) in skill.doc.lines().enumerate() {
-            if line.to_lowercase().contains(&query_lower) {
+            if line_matches(line) {
                 skill_matches.push((line_num, line.to_string(), "doc"));
             }
         }
@@ -304,7 +581,7 @@ fn cmd_search(skills: &[skill::Skill], query: &str, context_lines: usize) {
         // Search in extra docs
         for extra in &skill.extra_docs {
             for (line_num, line) in extra.contents.lines().enumerate() {
-                if line.to_lowercase().contains(&query_lower) {
+                if line_matches(line) {
                     skill_matches.push((line_num, line.to_string(), extra.name.as_str()));
                 }
             }
@@ -340,26 +617,26 @@ fn cmd_search(skills: &[skill::Skill], query: &str, context_lines: usize) {
                     let end = (*line_num + context_lines + 1).min(lines.len());
 
                     if start < *line_num || end > *line_num + 1 {
-                        for i in start..end {
+                        for (i, context_line) in lines.iter().enumerate().take(end).skip(start) {
                             if i != *line_num {
-                                println!("    L{}: {}", i + 1, lines[i].trim());
+                                println!("    L{}: {}", i + 1, context_line.trim());
                             }
                         }
                     }
                 }
             }
             total_matches += skill_matches.len();
+            matched_skills += 1;
         }
     }
 
     if total_matches == 0 {
         println!("No matches found for '{}'", query);
     } else {
-        println!("\n{} total matches across {} skills", total_matches,
-            skills.iter().filter(|s| {
-                s.doc.to_lowercase().contains(&query_lower) ||
-                s.extra_docs.iter().any(|e| e.contents.to_lowercase().contains(&query_lower))
-            }).count()
+        println!(
+            "\n{} total matches across {} skills",
+            total_matches, matched_skills
         );
     }
+    Ok(())
 }
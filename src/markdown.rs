@@ -0,0 +1,159 @@
+//! Minimal markdown block parsing for skill bodies.
+//!
+//! Only the structure that matters for ranking is recognized: ATX headings,
+//! fenced code blocks, list items and paragraphs. Inline markup is kept as
+//! plain text.
+
+/// A top-level block of a markdown document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    /// `#`-style heading with its level (1-6).
+    Heading { level: usize, text: String },
+    /// Consecutive non-blank lines of running text.
+    Paragraph(String),
+    /// One bullet or numbered list item, including its continuation lines.
+    ListItem(String),
+    /// Fenced code block with its info-string language, if any.
+    Code { lang: Option<String>, text: String },
+}
+
+/// Split a markdown document into blocks, in document order.
+/// An unterminated code fence runs to the end of the document.
+pub fn parse_blocks(text: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines();
+    // Text of the paragraph or list item being accumulated.
+    let mut pending: Option<Block> = None;
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if let Some(fence) = fence_marker(trimmed) {
+            blocks.extend(pending.take());
+            let info = trimmed.trim_start_matches(fence).trim();
+            let lang = info
+                .split_whitespace()
+                .next()
+                .map(|l| l.to_lowercase());
+            let code: Vec<&str> = lines
+                .by_ref()
+                .take_while(|l| !l.trim().starts_with(fence))
+                .collect();
+            blocks.push(Block::Code {
+                lang,
+                text: code.join("\n"),
+            });
+        } else if trimmed.is_empty() {
+            blocks.extend(pending.take());
+        } else if let Some((level, text)) = heading(trimmed) {
+            blocks.extend(pending.take());
+            blocks.push(Block::Heading {
+                level,
+                text: text.to_string(),
+            });
+        } else if let Some(item) = list_item(trimmed) {
+            blocks.extend(pending.take());
+            pending = Some(Block::ListItem(item.to_string()));
+        } else {
+            match &mut pending {
+                Some(Block::Paragraph(text) | Block::ListItem(text)) => {
+                    text.push(' ');
+                    text.push_str(trimmed);
+                }
+                _ => pending = Some(Block::Paragraph(trimmed.to_string())),
+            }
+        }
+    }
+    blocks.extend(pending);
+    blocks
+}
+
+/// The fence (```` ``` ```` or `~~~`) opening a code block, if any.
+fn fence_marker(line: &str) -> Option<&'static str> {
+    ["```", "~~~"].into_iter().find(|f| line.starts_with(f))
+}
+
+/// Level and text of an ATX heading (`## Title`, optionally `## Title ##`).
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with(' ')) {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+/// Text of a `-`, `*`, `+` or `1.`/`1)` list item.
+fn list_item(line: &str) -> Option<&str> {
+    if let Some(rest) = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))
+    {
+        return Some(rest.trim());
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let rest = &line[digits..];
+    if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+        return Some(rest[2..].trim());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_headings_lists_paragraphs_and_code() {
+        let doc = "\
+# Overview
+Use this skill
+for layouts.
+
+## Steps
+- read the spec
+  carefully
+2. write code
+
+```swift
+let view = Text(\"hi\")
+```
+";
+        assert_eq!(
+            parse_blocks(doc),
+            vec![
+                Block::Heading {
+                    level: 1,
+                    text: "Overview".into()
+                },
+                Block::Paragraph("Use this skill for layouts.".into()),
+                Block::Heading {
+                    level: 2,
+                    text: "Steps".into()
+                },
+                Block::ListItem("read the spec carefully".into()),
+                Block::ListItem("write code".into()),
+                Block::Code {
+                    lang: Some("swift".into()),
+                    text: "let view = Text(\"hi\")".into()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_lines_inside_code_are_not_headings() {
+        let doc = "~~~\n# not a heading\n~~~\n#hashtag";
+        assert_eq!(
+            parse_blocks(doc),
+            vec![
+                Block::Code {
+                    lang: None,
+                    text: "# not a heading".into()
+                },
+                Block::Paragraph("#hashtag".into()),
+            ]
+        );
+    }
+}
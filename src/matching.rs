@@ -2,40 +2,91 @@
 
 use std::collections::HashSet;
 
+use serde::Serialize;
 use strsim::jaro_winkler;
 
-use crate::skill::{normalized_tokens, Skill};
+use crate::bm25::Bm25Index;
+use crate::classifier::Classifier;
+use crate::config::RankingConfig;
+use crate::context::RepoContext;
+use crate::glossary::Glossary;
+use crate::history::History;
+use crate::skill::{normalized_tokens, shingles, BodySection, Skill};
+use crate::spelling::Vocabulary;
+use crate::syntax::{contains_sequence, QuerySyntax};
+use crate::synonyms::Synonyms;
 
 /// Scoring signals used to rank skill matches.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct SkillSignals {
     pub name_hits: usize,
     pub summary_hits: usize,
     pub tag_hits: usize,
+    /// Query tokens matching the skill's declared aliases.
+    pub alias_hits: usize,
+    /// Synonym-expanded tokens matching the name, summary, tags or aliases.
+    pub expanded_hits: usize,
+    /// Typo-corrected tokens matching the name, summary, tags or aliases.
+    pub fuzzy_hits: usize,
     pub body_hits: usize,
+    /// BM25 relevance of the body's headings, scaled to an integer.
+    pub heading_bm25: usize,
+    /// BM25 relevance of the body's paragraphs and list items, scaled.
+    pub prose_bm25: usize,
+    /// BM25 relevance of the body's code blocks, scaled.
+    pub code_bm25: usize,
+    /// Query tokens found in the best-matching extra doc.
+    pub extras_hits: usize,
+    /// Extra doc (relative path) that contributed the most, if any.
+    pub best_extra: Option<String>,
     pub phrase_bonus: usize,
+    /// Query bigrams/trigrams found verbatim in the skill.
+    pub phrase_hits: usize,
     pub name_similarity: usize,
     pub summary_similarity: usize,
+    /// `avoid_when` triggers whose words all appear, in order, in the query.
+    pub avoid_hits: usize,
+    /// TF-IDF cosine similarity as a percentage (semantic mode only).
+    pub semantic_similarity: usize,
+    /// Trained classifier probability for the skill, scaled to 0-10.
+    pub model_score: usize,
+    /// The skill's `applies_to` markers found in the project context.
+    pub context_hits: usize,
+    /// Decayed weights of history tokens matching the name, summary, tags or
+    /// aliases, summed and scaled to tenths of a hit.
+    pub history_score: usize,
+    /// 1 when the skill was selected in the previous turn, else 0.
+    pub sticky: usize,
 }
 
 impl SkillSignals {
-    /// Calculate the total weighted score for this skill match.
-    pub fn total_score(&self) -> usize {
-        const NAME_WEIGHT: usize = 8;
-        const SUMMARY_WEIGHT: usize = 5;
-        const TAG_WEIGHT: usize = 4;
-        const BODY_WEIGHT: usize = 1;
-        const PHRASE_WEIGHT: usize = 1;
-        const NAME_SIM_WEIGHT: usize = 2;
-        const SUMMARY_SIM_WEIGHT: usize = 1;
+    /// Weighted penalty from negative triggers.
+    pub fn penalty(&self, weights: &RankingConfig) -> usize {
+        weights.avoid_penalty * self.avoid_hits
+    }
 
-        NAME_WEIGHT * self.name_hits
-            + SUMMARY_WEIGHT * self.summary_hits
-            + TAG_WEIGHT * self.tag_hits
-            + BODY_WEIGHT * self.body_hits
-            + PHRASE_WEIGHT * self.phrase_bonus
-            + NAME_SIM_WEIGHT * self.name_similarity
-            + SUMMARY_SIM_WEIGHT * self.summary_similarity
+    /// Calculate the total weighted score for this skill match.
+    /// Penalties reduce the score but never below zero.
+    pub fn total_score(&self, weights: &RankingConfig) -> usize {
+        let positive = weights.name_weight * self.name_hits
+            + weights.summary_weight * self.summary_hits
+            + weights.tag_weight * self.tag_hits
+            + weights.alias_weight * self.alias_hits
+            + weights.expanded_weight * self.expanded_hits
+            + weights.fuzzy_weight * self.fuzzy_hits
+            + weights.heading_weight * self.heading_bm25
+            + weights.prose_weight * self.prose_bm25
+            + weights.code_weight * self.code_bm25
+            + weights.extras_weight * self.extras_hits
+            + weights.phrase_weight * self.phrase_bonus
+            + weights.phrase_hit_weight * self.phrase_hits
+            + weights.name_similarity_weight * self.name_similarity
+            + weights.summary_similarity_weight * self.summary_similarity
+            + weights.model_weight * self.model_score
+            + weights.context_weight * self.context_hits
+            + weights.history_weight * self.history_score
+            + weights.stickiness_weight * self.sticky;
+        positive.saturating_sub(self.penalty(weights))
     }
 }
 
@@ -48,19 +99,174 @@ pub fn overlap(query_tokens: &[String], target_tokens: &[String]) -> usize {
         .count()
 }
 
+/// Scale factor applied to raw BM25 scores before rounding.
+const BM25_SCALE: f64 = 2.0;
+
+/// IDF bonus for tokens naming some skill's routing fields when a long
+/// query is cut down: they beat body-only words of similar rarity, but a
+/// routing word shared by every skill still loses to a rare one.
+const ROUTING_IDF_BONUS: f64 = 1.5;
+
+/// A query tokenized and expanded once, then scored against every skill.
+#[derive(Debug, Clone, Default)]
+pub struct PreparedQuery {
+    /// Normalized query tokens.
+    pub tokens: Vec<String>,
+    /// Synonym expansions not present in the query itself.
+    pub expanded: Vec<String>,
+    /// Typo corrections applied to query words, as (original, corrected).
+    pub corrections: Vec<(String, String)>,
+    /// Tokens of corrected words, scored at a reduced weight.
+    pub corrected: Vec<String>,
+    /// Bigram and trigram shingles of the query tokens.
+    pub shingles: Vec<String>,
+    /// Lowercased query text, without operators, used for substring and
+    /// similarity checks.
+    pub phrase: String,
+    /// Phrase, exclusion and field constraints a skill must satisfy.
+    pub syntax: QuerySyntax,
+    /// Tokens from earlier conversation turns with their decayed weights,
+    /// excluding tokens of the query itself.
+    pub history: Vec<(String, f64)>,
+    /// Skill selected in the previous turn, if known.
+    pub previous_skill: Option<String>,
+    /// Whether a long query was cut down to its most distinctive tokens.
+    pub reduced: bool,
+}
+
+impl PreparedQuery {
+    /// Translate a query's text through `glossary`, then tokenize it,
+    /// expand it through `synonyms` and correct words unknown to
+    /// `vocabulary`. Callers decide whether the raw text is parsed for query
+    /// syntax ([`QuerySyntax::parse`]) or taken as is ([`QuerySyntax::plain`]).
+    pub fn new(
+        mut syntax: QuerySyntax,
+        glossary: &Glossary,
+        synonyms: &Synonyms,
+        vocabulary: &Vocabulary,
+    ) -> Self {
+        syntax.translate(|text| glossary.translate(text));
+        let query = syntax.text.as_str();
+        let tokens = normalized_tokens(query);
+        let expanded = synonyms.expand(&tokens);
+        let shingles = shingles(&tokens);
+        let corrections = vocabulary.corrections(query);
+        let mut corrected: Vec<String> = corrections
+            .iter()
+            .flat_map(|(_, fixed)| normalized_tokens(fixed))
+            .filter(|t| !tokens.contains(t))
+            .collect();
+        corrected.dedup();
+        Self {
+            tokens,
+            expanded,
+            corrections,
+            corrected,
+            shingles,
+            phrase: query.to_lowercase(),
+            syntax,
+            history: Vec::new(),
+            previous_skill: None,
+            reduced: false,
+        }
+    }
+
+    /// Blend in earlier turns: their tokens weighted by `decay` per turn of
+    /// age, and the skill picked last.
+    pub fn add_history(&mut self, history: &History, decay: f64) {
+        self.history = history
+            .weighted_tokens(decay)
+            .into_iter()
+            .filter(|(token, _)| !self.tokens.contains(token))
+            .collect();
+        self.previous_skill = history.previous_skill().map(str::to_string);
+    }
+
+    /// Reduce a query with more than `limit` distinct tokens to its `limit`
+    /// most distinctive ones, so boilerplate in long issue or PR text does
+    /// not dilute the ranking. Tokens are ordered by descending IDF, boosted
+    /// for tokens naming some skill's routing fields; tokens no skill
+    /// contains are dropped. Synonym expansions are recomputed from the kept
+    /// tokens, shingles are kept only when all their words survive, and typo
+    /// corrections are discarded as noise. A `limit` of 0 keeps everything.
+    pub fn keep_distinctive(&mut self, index: &Bm25Index, synonyms: &Synonyms, limit: usize) {
+        let mut unique: Vec<&String> = Vec::new();
+        for token in &self.tokens {
+            if !unique.contains(&token) {
+                unique.push(token);
+            }
+        }
+        if limit == 0 || unique.len() <= limit {
+            return;
+        }
+
+        let mut by_rarity: Vec<&String> =
+            unique.iter().copied().filter(|t| index.contains(t)).collect();
+        let rarity = |token: &str| {
+            let bonus = if index.is_routing_term(token) {
+                ROUTING_IDF_BONUS
+            } else {
+                0.0
+            };
+            index.idf(token) + bonus
+        };
+        by_rarity.sort_by(|a, b| rarity(b).total_cmp(&rarity(a)));
+        let kept: HashSet<&str> = by_rarity.into_iter().take(limit).map(String::as_str).collect();
+
+        // Preserve query order so the remaining tokens still read naturally.
+        let tokens: Vec<String> = unique
+            .into_iter()
+            .filter(|t| kept.contains(t.as_str()))
+            .cloned()
+            .collect();
+        self.shingles
+            .retain(|s| s.split(' ').all(|word| kept.contains(word)));
+        self.expanded = synonyms.expand(&tokens);
+        self.tokens = tokens;
+        self.corrections.clear();
+        self.corrected.clear();
+        self.reduced = true;
+    }
+}
+
 /// Compute matching signals between a query and a skill.
-pub fn compute_signals(skill: &Skill, query_tokens: &[String], query_phrase: &str) -> SkillSignals {
+pub fn compute_signals(
+    skill: &Skill,
+    index: &Bm25Index,
+    query: &PreparedQuery,
+    ranking: &RankingConfig,
+) -> SkillSignals {
+    let query_tokens = &query.tokens;
+    let query_phrase = query.phrase.as_str();
     // Use pre-computed cached tokens from the Skill struct
+    let routing_tokens: Vec<String> = skill
+        .name_tokens
+        .iter()
+        .chain(&skill.summary_tokens)
+        .chain(&skill.tag_tokens)
+        .chain(&skill.alias_tokens)
+        .cloned()
+        .collect();
+    let alias_hits = overlap(query_tokens, &skill.alias_tokens);
+    let expanded_hits = overlap(&query.expanded, &routing_tokens);
+    let fuzzy_hits = overlap(&query.corrected, &routing_tokens);
+    let phrase_hits = overlap(&query.shingles, &skill.shingles);
     let base_hits = overlap(query_tokens, &skill.name_tokens)
         + overlap(query_tokens, &skill.summary_tokens)
         + overlap(query_tokens, &skill.tag_tokens)
-        + overlap(query_tokens, &skill.body_tokens);
+        + overlap(query_tokens, &skill.body_tokens)
+        + alias_hits
+        + expanded_hits
+        + fuzzy_hits
+        + phrase_hits;
 
     let name_sim_raw = jaro_winkler(&skill.name.to_lowercase(), query_phrase);
     let summary_sim_raw = jaro_winkler(&skill.summary.to_lowercase(), query_phrase);
 
     // Only trust similarity when we also have token agreement or the match is very strong.
-    let similarity_gate = base_hits > 0 || name_sim_raw >= 0.92 || summary_sim_raw >= 0.94;
+    let similarity_gate = base_hits > 0
+        || name_sim_raw >= ranking.name_similarity_gate
+        || summary_sim_raw >= ranking.summary_similarity_gate;
     let name_similarity = if similarity_gate {
         (name_sim_raw * 10.0).round() as usize
     } else {
@@ -72,6 +278,21 @@ pub fn compute_signals(skill: &Skill, query_tokens: &[String], query_phrase: &st
         0
     };
 
+    // Pick the extra doc with the highest BM25 relevance; count its token hits.
+    let best_extra = skill
+        .extra_docs
+        .iter()
+        .map(|e| (index.score_extra(query_tokens, e), e))
+        .filter(|(score, _)| *score > 0.0)
+        .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    let extras_hits = best_extra
+        .map(|(_, e)| overlap(query_tokens, &e.tokens))
+        .unwrap_or(0);
+
+    let section_bm25 = |section| {
+        (index.score_section(query_tokens, skill, section) * BM25_SCALE).round() as usize
+    };
+
     let phrase_bonus = if skill.name.to_lowercase().contains(query_phrase)
         || skill.summary.to_lowercase().contains(query_phrase)
     {
@@ -84,34 +305,318 @@ pub fn compute_signals(skill: &Skill, query_tokens: &[String], query_phrase: &st
         name_hits: overlap(query_tokens, &skill.name_tokens),
         summary_hits: overlap(query_tokens, &skill.summary_tokens),
         tag_hits: overlap(query_tokens, &skill.tag_tokens),
+        alias_hits,
+        expanded_hits,
+        fuzzy_hits,
         body_hits: overlap(query_tokens, &skill.body_tokens),
+        heading_bm25: section_bm25(BodySection::Headings),
+        prose_bm25: section_bm25(BodySection::Prose),
+        code_bm25: section_bm25(BodySection::Code),
+        extras_hits,
+        best_extra: best_extra.map(|(_, e)| e.name.clone()),
         phrase_bonus,
+        phrase_hits,
         name_similarity,
         summary_similarity,
+        avoid_hits: skill
+            .avoid_tokens
+            .iter()
+            .filter(|trigger| contains_sequence(query_tokens, trigger))
+            .count(),
+        semantic_similarity: 0,
+        model_score: 0,
+        context_hits: 0,
+        history_score: 0,
+        sticky: 0,
+    }
+}
+
+/// Where an explained token came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenOrigin {
+    Query,
+    Expanded,
+    Corrected,
+}
+
+/// Weighted contribution of one query token to a skill's score.
+#[derive(Debug, Clone)]
+pub struct TokenContribution {
+    pub token: String,
+    pub origin: TokenOrigin,
+    /// Fields the token matched (name, summary, tag, alias, heading, prose,
+    /// code, extras).
+    pub fields: Vec<&'static str>,
+    pub contribution: f64,
+}
+
+/// Break a skill's token-based score down per query token.
+/// Body section contributions are unrounded BM25 terms, so they may differ
+/// slightly from the rounded `heading_bm25`, `prose_bm25` and `code_bm25`
+/// signals. Non-token signals (phrase bonus,
+/// phrase hits, similarity, penalties) are not included.
+pub fn explain_tokens(
+    skill: &Skill,
+    index: &Bm25Index,
+    query: &PreparedQuery,
+    signals: &SkillSignals,
+    ranking: &RankingConfig,
+) -> Vec<TokenContribution> {
+    let query_tokens = &query.tokens;
+    let best_extra = signals
+        .best_extra
+        .as_deref()
+        .and_then(|name| skill.extra_docs.iter().find(|e| e.name == name));
+
+    let mut seen = HashSet::new();
+    let mut rows = Vec::new();
+    for token in query_tokens {
+        if !seen.insert(token.as_str()) {
+            continue;
+        }
+        let count = query_tokens.iter().filter(|t| *t == token).count();
+        let single = std::slice::from_ref(token);
+        let mut fields = Vec::new();
+        let mut contribution = 0.0;
+
+        for (field, tokens, weight) in [
+            ("name", &skill.name_tokens, ranking.name_weight),
+            ("summary", &skill.summary_tokens, ranking.summary_weight),
+            ("tag", &skill.tag_tokens, ranking.tag_weight),
+            ("alias", &skill.alias_tokens, ranking.alias_weight),
+        ] {
+            if tokens.contains(token) {
+                fields.push(field);
+                contribution += (weight * count) as f64;
+            }
+        }
+        for section in BodySection::ALL {
+            let bm25 = index.score_section(single, skill, section) * BM25_SCALE;
+            if bm25 > 0.0 {
+                fields.push(section.label());
+                contribution += bm25 * ranking.section_weight(section) as f64;
+            }
+        }
+        if best_extra.is_some_and(|e| e.tokens.contains(token)) {
+            fields.push("extras");
+            contribution += (ranking.extras_weight * count) as f64;
+        }
+
+        rows.push(TokenContribution {
+            token: token.clone(),
+            origin: TokenOrigin::Query,
+            fields,
+            contribution,
+        });
+    }
+
+    let derived = query
+        .expanded
+        .iter()
+        .map(|t| (t, TokenOrigin::Expanded, ranking.expanded_weight))
+        .chain(
+            query
+                .corrected
+                .iter()
+                .map(|t| (t, TokenOrigin::Corrected, ranking.fuzzy_weight)),
+        );
+    for (token, origin, weight) in derived {
+        let fields: Vec<&'static str> = [
+            ("name", &skill.name_tokens),
+            ("summary", &skill.summary_tokens),
+            ("tag", &skill.tag_tokens),
+            ("alias", &skill.alias_tokens),
+        ]
+        .into_iter()
+        .filter(|(_, tokens)| tokens.contains(token))
+        .map(|(field, _)| field)
+        .collect();
+        let contribution = if fields.is_empty() { 0.0 } else { weight as f64 };
+        rows.push(TokenContribution {
+            token: token.clone(),
+            origin,
+            fields,
+            contribution,
+        });
+    }
+
+    rows
+}
+
+/// How clearly a ranking answers a query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The top result is confident and well ahead of the runner-up.
+    Clear,
+    /// The top results are too close to call; ask the user.
+    Ambiguous,
+    /// No result reaches the minimum confidence.
+    NoMatch,
+}
+
+impl Verdict {
+    /// Short human-readable label for output.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Clear => "clear winner",
+            Verdict::Ambiguous => "ambiguous",
+            Verdict::NoMatch => "nothing fits",
+        }
+    }
+}
+
+/// Score relative to what a skill would earn if every query token hit both
+/// its name and summary and its name/summary were near-identical to the
+/// query, so scores are comparable across queries of different lengths.
+/// Body BM25, extras, phrase, model, context and history signals are left out
+/// of that reference because they have no fixed ceiling; they can only raise
+/// a score, so a strong skill may exceed 1.0; displayed confidences cap it
+/// there.
+pub fn relative_score(score: usize, query_token_count: usize, ranking: &RankingConfig) -> f64 {
+    let reference = confidence_reference(query_token_count, ranking);
+    if reference == 0 {
+        return 0.0;
+    }
+    score as f64 / reference as f64
+}
+
+/// Score treated as full confidence for a query of `query_token_count` tokens.
+pub fn confidence_reference(query_token_count: usize, ranking: &RankingConfig) -> usize {
+    query_token_count.max(1) * (ranking.name_weight + ranking.summary_weight)
+        + ranking.name_similarity_weight * 10
+        + ranking.summary_similarity_weight * 8
+}
+
+/// Gap between the top result and the best of the rest, from relative
+/// scores rather than capped confidences so two skills past the cap still
+/// differ. The rest may be re-ordered (e.g. by diversity re-ranking), so the
+/// runner-up is the highest of them rather than the second entry.
+pub fn margin(relative_scores: &[f64]) -> f64 {
+    let Some((&best, rest)) = relative_scores.split_first() else {
+        return 0.0;
+    };
+    best - rest.iter().copied().fold(0.0, f64::max)
+}
+
+/// Classify a ranking from its relative scores, top result first.
+pub fn verdict(relative_scores: &[f64], ranking: &RankingConfig) -> Verdict {
+    let Some(&best) = relative_scores.first() else {
+        return Verdict::NoMatch;
+    };
+    if best <= 0.0 || best < ranking.min_confidence {
+        return Verdict::NoMatch;
+    }
+    if relative_scores.len() > 1 && margin(relative_scores) < ranking.ambiguity_margin {
+        Verdict::Ambiguous
+    } else {
+        Verdict::Clear
     }
 }
 
-/// Rank skills by how well they match a query.
+/// Rank skills by how well they match a prepared query.
+/// `index` holds corpus statistics built once over `skills`, and signals are
+/// weighted according to `ranking`. A trained `model` that is current for
+/// `skills` adds its per-skill probabilities as the `model_score` signal, and
+/// project markers in `context` boost the skills that declared them. History
+/// tokens and the previously selected skill in `query` add their boosts. Skills
+/// failing the query's phrase, exclusion or field constraints are left out.
 /// Returns a sorted vector of (score, skill reference, signals).
 pub fn rank_skills<'a>(
     skills: &'a [Skill],
-    query: &str,
+    index: &Bm25Index,
+    query: &PreparedQuery,
+    ranking: &RankingConfig,
+    model: Option<&Classifier>,
+    context: &RepoContext,
 ) -> Vec<(usize, &'a Skill, SkillSignals)> {
-    let q_tokens = normalized_tokens(query);
-    let query_phrase = query.to_lowercase();
-
+    let probabilities = model.and_then(|m| m.probabilities(&query.tokens));
     let mut ranked: Vec<(usize, &Skill, SkillSignals)> = skills
         .iter()
+        .filter(|s| query.syntax.admits(s))
         .map(|s| {
-            let signals = compute_signals(s, &q_tokens, &query_phrase);
-            (signals.total_score(), s, signals)
+            let mut signals = compute_signals(s, index, query, ranking);
+            if let Some(p) = probabilities.as_ref().and_then(|p| p.get(&s.name)) {
+                signals.model_score = (p * 10.0).round() as usize;
+            }
+            signals.context_hits = context.hits(s);
+            signals.history_score = history_score(s, query);
+            signals.sticky = usize::from(query.previous_skill.as_deref() == Some(s.name.as_str()));
+            (signals.total_score(ranking), s, signals)
         })
         .collect();
 
-    ranked.sort_by(|a, b| b.0.cmp(&a.0));
+    ranked.sort_by_key(|r| std::cmp::Reverse(r.0));
     ranked
 }
 
+/// Sum of the decayed weights of history tokens found in a skill's name,
+/// summary, tags or aliases, in tenths.
+fn history_score(skill: &Skill, query: &PreparedQuery) -> usize {
+    let total: f64 = query
+        .history
+        .iter()
+        .filter(|(token, _)| {
+            [
+                &skill.name_tokens,
+                &skill.summary_tokens,
+                &skill.tag_tokens,
+                &skill.alias_tokens,
+            ]
+            .iter()
+            .any(|field| field.contains(token))
+        })
+        .map(|(_, weight)| weight)
+        .sum();
+    (total * 10.0).round() as usize
+}
+
+/// Words that join separate intents in a multi-part query.
+const INTENT_CONJUNCTIONS: &[&str] = &["and", "then", "also", "plus", "afterwards", "finally"];
+
+/// Split a query into separate intents at sentence boundaries and
+/// conjunctions ("design the screens and then write tests" -> ["design the
+/// screens", "write tests"]). Parts without any matchable token are dropped.
+pub fn split_intents(query: &str) -> Vec<String> {
+    let mut intents = Vec::new();
+    for sentence in sentences(query) {
+        let mut words: Vec<&str> = Vec::new();
+        for word in sentence.split_whitespace() {
+            let bare = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+            if INTENT_CONJUNCTIONS.contains(&bare.as_str()) {
+                intents.push(words.join(" "));
+                words.clear();
+            } else {
+                words.push(word.trim_end_matches(','));
+            }
+        }
+        intents.push(words.join(" "));
+    }
+    intents.retain(|intent| !normalized_tokens(intent).is_empty());
+    intents
+}
+
+/// Split text at `;`, line breaks and sentence-ending `.`, `!` or `?`.
+/// Punctuation only ends a sentence when followed by whitespace or the end
+/// of the text, so "Cargo.toml" or "v1.2" stay whole.
+fn sentences(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let ends = match c {
+            ';' | '\n' => true,
+            '.' | '!' | '?' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if ends {
+            parts.push(&text[start..i]);
+            start = i + c.len_utf8();
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
 /// Find closest skill names using Jaro-Winkler similarity.
 /// Used when no good match is found.
 pub fn closest_skill_names<'a>(skills: &'a [Skill], query: &str, limit: usize) -> Vec<&'a str> {
@@ -135,6 +640,11 @@ pub fn closest_skill_names<'a>(skills: &'a [Skill], query: &str, limit: usize) -
 mod tests {
     use super::*;
 
+    fn prepare(query: &str, synonyms: &Synonyms, vocabulary: &Vocabulary) -> PreparedQuery {
+        let glossary = Glossary::default();
+        PreparedQuery::new(QuerySyntax::parse(query), &glossary, synonyms, vocabulary)
+    }
+
     #[test]
     fn test_overlap_counts_matching_tokens() {
         let query = vec!["swift".to_string(), "ios".to_string(), "app".to_string()];
@@ -155,12 +665,181 @@ mod tests {
             name_hits: 1,
             summary_hits: 1,
             tag_hits: 1,
-            body_hits: 1,
+            body_hits: 3,
+            heading_bm25: 1,
+            prose_bm25: 1,
+            code_bm25: 1,
             phrase_bonus: 10,
             name_similarity: 5,
             summary_similarity: 4,
+            ..Default::default()
+        };
+        // Body hits are informational; per-section BM25 carries the body weight.
+        // 8*1 + 5*1 + 4*1 + 3*1 + 1*1 + 1*1 + 1*10 + 2*5 + 1*4 = 46
+        assert_eq!(signals.total_score(&RankingConfig::default()), 46);
+    }
+
+    #[test]
+    fn test_relative_score_is_length_normalized() {
+        let weights = RankingConfig::default();
+        assert_eq!(relative_score(0, 3, &weights), 0.0);
+        assert!(relative_score(10_000, 1, &weights) > 1.0);
+        assert!(relative_score(40, 1, &weights) > relative_score(40, 4, &weights));
+    }
+
+    #[test]
+    fn test_verdict_distinguishes_clear_ambiguous_and_no_match() {
+        let weights = RankingConfig::default();
+        assert_eq!(verdict(&[0.8, 0.3], &weights), Verdict::Clear);
+        assert_eq!(verdict(&[0.8, 0.78], &weights), Verdict::Ambiguous);
+        assert_eq!(verdict(&[0.1, 0.05], &weights), Verdict::NoMatch);
+        assert_eq!(verdict(&[], &weights), Verdict::NoMatch);
+        assert_eq!(verdict(&[0.6], &weights), Verdict::Clear);
+        // A diversified #2 does not hide a close runner-up further down.
+        assert_eq!(verdict(&[0.8, 0.5, 0.79], &weights), Verdict::Ambiguous);
+        // Skills past the display cap are still told apart.
+        assert_eq!(verdict(&[1.5, 1.2, 1.1], &weights), Verdict::Clear);
+    }
+
+    #[test]
+    fn test_explain_tokens_attributes_fields_and_weights() {
+        let skill = Skill::for_test("swift-testing")
+            .tags(&["xctest"])
+            .body("# Testing\n\nwrite swift tests");
+        let skills = vec![skill];
+        let index = Bm25Index::build(&skills);
+        let weights = RankingConfig::default();
+        let query = prepare("swift xctest", &Synonyms::default(), &Vocabulary::default());
+        let signals = compute_signals(&skills[0], &index, &query, &weights);
+
+        let rows = explain_tokens(&skills[0], &index, &query, &signals, &weights);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].fields, vec!["name", "prose"]);
+        assert!(rows[0].contribution >= weights.name_weight as f64);
+        assert_eq!(rows[1].fields, vec!["tag"]);
+        assert_eq!(rows[1].contribution, weights.tag_weight as f64);
+    }
+
+    #[test]
+    fn test_adjacent_phrase_outscores_scattered_words() {
+        let adjacent = Skill {
+            name: "adjacent".to_string(),
+            body_tokens: normalized_tokens("configure push notifications for the app"),
+            shingles: shingles(&normalized_tokens("configure push notifications for the app")),
+            ..Default::default()
+        };
+        let scattered = Skill {
+            name: "scattered".to_string(),
+            body_tokens: normalized_tokens("push the button to read notifications later"),
+            shingles: shingles(&normalized_tokens("push the button to read notifications later")),
+            ..Default::default()
+        };
+        let skills = vec![adjacent, scattered];
+        let index = Bm25Index::build(&skills);
+        let weights = RankingConfig::default();
+        let query = prepare("push notifications", &Synonyms::default(), &Vocabulary::default());
+
+        let near = compute_signals(&skills[0], &index, &query, &weights);
+        let far = compute_signals(&skills[1], &index, &query, &weights);
+        assert_eq!(near.phrase_hits, 1);
+        assert_eq!(far.phrase_hits, 0);
+        assert!(near.total_score(&weights) > far.total_score(&weights));
+    }
+
+    #[test]
+    fn test_misspelled_tokens_score_as_fuzzy_hits() {
+        let skill = Skill::for_test("swift-concurrency");
+        let skills = vec![skill];
+        let index = Bm25Index::build(&skills);
+        let vocabulary = Vocabulary::build(&skills);
+        let weights = RankingConfig::default();
+        let query = prepare("concurency", &Synonyms::default(), &vocabulary);
+
+        assert_eq!(
+            query.corrections,
+            vec![("concurency".to_string(), "concurrency".to_string())]
+        );
+        let signals = compute_signals(&skills[0], &index, &query, &weights);
+        assert_eq!(signals.name_hits, 0);
+        assert_eq!(signals.fuzzy_hits, 1);
+    }
+
+    #[test]
+    fn test_long_queries_keep_distinctive_tokens() {
+        let common = "please thanks team project";
+        let skills: Vec<Skill> = ["storekit receipts", "layout grid", "release notes"]
+            .iter()
+            .map(|name| Skill::for_test(name).body(common))
+            .collect();
+        let index = Bm25Index::build(&skills);
+        let mut query = prepare(
+            "thanks team please check storekit receipts project zzz",
+            &Synonyms::default(),
+            &Vocabulary::default(),
+        );
+        query.keep_distinctive(&index, &Synonyms::default(), 2);
+
+        assert!(query.reduced);
+        assert_eq!(query.tokens, normalized_tokens("storekit receipts"));
+        assert_eq!(query.shingles, vec!["storekit receipt".to_string()]);
+
+        let mut short = prepare("storekit", &Synonyms::default(), &Vocabulary::default());
+        short.keep_distinctive(&index, &Synonyms::default(), 2);
+        assert!(!short.reduced);
+    }
+
+    #[test]
+    fn test_distinctive_tokens_rank_by_rarity_and_prune_expansions() {
+        // "swift" names every skill, "keychain" appears in one body only.
+        let skills: Vec<Skill> = (0..8)
+            .map(|i| Skill::for_test("swift").body(if i == 0 { "keychain" } else { "thanks" }))
+            .collect();
+        let index = Bm25Index::build(&skills);
+        let table = std::collections::HashMap::from([(
+            "swift".to_string(),
+            vec!["apple".to_string()],
+        )]);
+        let synonyms = Synonyms::from_table(&table);
+        let mut query = prepare("swift keychain thanks", &synonyms, &Vocabulary::default());
+        assert_eq!(query.expanded, normalized_tokens("apple"));
+
+        query.keep_distinctive(&index, &synonyms, 1);
+        assert_eq!(query.tokens, normalized_tokens("keychain"));
+        assert!(query.expanded.is_empty());
+    }
+
+    #[test]
+    fn test_split_intents_on_conjunctions_and_sentences() {
+        assert_eq!(
+            split_intents("Design the onboarding screens and then write XCTest coverage."),
+            vec!["Design the onboarding screens", "write XCTest coverage"]
+        );
+        assert_eq!(
+            split_intents("fix the crash; also, update docs"),
+            vec!["fix the crash", "update docs"]
+        );
+        assert_eq!(split_intents("profile scrolling"), vec!["profile scrolling"]);
+        assert_eq!(
+            split_intents("update Cargo.toml and fix the build. Ship v1.2!"),
+            vec!["update Cargo.toml", "fix the build", "Ship v1.2"]
+        );
+        assert!(split_intents("and then").is_empty());
+    }
+
+    #[test]
+    fn test_avoid_hits_penalize_without_underflow() {
+        let weights = RankingConfig::default();
+        let signals = SkillSignals {
+            name_hits: 2,
+            avoid_hits: 1,
+            ..Default::default()
+        };
+        assert_eq!(signals.total_score(&weights), 16 - weights.avoid_penalty);
+
+        let swamped = SkillSignals {
+            avoid_hits: 3,
+            ..signals
         };
-        // 8*1 + 5*1 + 4*1 + 1*1 + 1*10 + 2*5 + 1*4 = 8 + 5 + 4 + 1 + 10 + 10 + 4 = 42
-        assert_eq!(signals.total_score(), 42);
+        assert_eq!(swamped.total_score(&weights), 0);
     }
 }
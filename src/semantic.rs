@@ -0,0 +1,201 @@
+//! Offline TF-IDF vector similarity ranking (`pick --mode semantic`).
+//!
+//! Skills and queries are embedded as sparse TF-IDF vectors over word tokens
+//! and their character trigrams, so related word forms ("auth" and
+//! "authentication") still overlap without any downloaded model.
+
+use std::collections::HashMap;
+
+use crate::matching::{PreparedQuery, SkillSignals};
+use crate::skill::Skill;
+
+/// Term-frequency multipliers per skill field.
+const NAME_BOOST: f64 = 3.0;
+const SUMMARY_BOOST: f64 = 2.0;
+const TAG_BOOST: f64 = 2.0;
+const BODY_BOOST: f64 = 1.0;
+
+/// Sparse vector keyed by feature string.
+type SparseVector = HashMap<String, f64>;
+
+/// TF-IDF vectors for every loaded skill, keyed by skill name.
+#[derive(Debug, Clone, Default)]
+pub struct SemanticIndex {
+    idf: HashMap<String, f64>,
+    vectors: HashMap<String, SparseVector>,
+}
+
+impl SemanticIndex {
+    /// Build vectors for all skills from their cached token fields.
+    pub fn build(skills: &[Skill]) -> Self {
+        let counts: Vec<(&Skill, SparseVector)> = skills
+            .iter()
+            .map(|skill| {
+                let mut tf = SparseVector::new();
+                for (tokens, boost) in [
+                    (&skill.name_tokens, NAME_BOOST),
+                    (&skill.summary_tokens, SUMMARY_BOOST),
+                    (&skill.tag_tokens, TAG_BOOST),
+                    (&skill.alias_tokens, TAG_BOOST),
+                    (&skill.body_tokens, BODY_BOOST),
+                ] {
+                    add_features(&mut tf, tokens, boost);
+                }
+                (skill, tf)
+            })
+            .collect();
+
+        let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+        for (_, tf) in &counts {
+            for feature in tf.keys() {
+                *doc_freq.entry(feature.as_str()).or_insert(0) += 1;
+            }
+        }
+        let n = skills.len() as f64;
+        let idf: HashMap<String, f64> = doc_freq
+            .into_iter()
+            .map(|(feature, df)| {
+                let weight = ((n + 1.0) / (df as f64 + 1.0)).ln() + 1.0;
+                (feature.to_string(), weight)
+            })
+            .collect();
+
+        let vectors = counts
+            .into_iter()
+            .map(|(skill, tf)| (skill.name.clone(), weigh(tf, &idf)))
+            .collect();
+
+        Self { idf, vectors }
+    }
+
+    /// Cosine similarity (0-1) between a query and a skill.
+    pub fn similarity(&self, query: &SparseVector, skill: &Skill) -> f64 {
+        let Some(vector) = self.vectors.get(&skill.name) else {
+            return 0.0;
+        };
+        query
+            .iter()
+            .filter_map(|(feature, q)| vector.get(feature).map(|s| q * s))
+            .sum()
+    }
+
+    /// Embed a prepared query, including synonym and typo expansions.
+    pub fn embed_query(&self, query: &PreparedQuery) -> SparseVector {
+        let mut tf = SparseVector::new();
+        add_features(&mut tf, &query.tokens, 1.0);
+        add_features(&mut tf, &query.expanded, 0.5);
+        add_features(&mut tf, &query.corrected, 0.5);
+        weigh(tf, &self.idf)
+    }
+}
+
+/// Re-rank lexical results by adding a TF-IDF cosine similarity bonus.
+/// `reference` is the lexical score treated as full confidence, so a cosine
+/// of 1.0 maps onto the same scale; `blend` weighs the bonus (0-1). The
+/// lexical score is kept whole: cosines are typically small, and trading
+/// score for them would push clear lexical matches below `min_confidence`.
+pub fn rerank(
+    ranked: &mut [(usize, &Skill, SkillSignals)],
+    index: &SemanticIndex,
+    query: &PreparedQuery,
+    blend: f64,
+    reference: usize,
+) {
+    let query_vector = index.embed_query(query);
+    for (score, skill, signals) in ranked.iter_mut() {
+        let cosine = index.similarity(&query_vector, skill);
+        signals.semantic_similarity = (cosine * 100.0).round() as usize;
+        let semantic_score = cosine * reference as f64;
+        *score += (blend * semantic_score).round() as usize;
+    }
+    ranked.sort_by_key(|r| std::cmp::Reverse(r.0));
+}
+
+/// Add word and character-trigram features for each token.
+fn add_features(tf: &mut SparseVector, tokens: &[String], boost: f64) {
+    for token in tokens {
+        *tf.entry(format!("w:{}", token)).or_insert(0.0) += boost;
+        let padded: Vec<char> = format!("^{}$", token).chars().collect();
+        for gram in padded.windows(3) {
+            let feature: String = std::iter::once('#').chain(gram.iter().copied()).collect();
+            *tf.entry(feature).or_insert(0.0) += boost * 0.5;
+        }
+    }
+}
+
+/// Apply sublinear TF, IDF weighting and L2 normalization.
+fn weigh(tf: SparseVector, idf: &HashMap<String, f64>) -> SparseVector {
+    let mut vector: SparseVector = tf
+        .into_iter()
+        .filter_map(|(feature, count)| {
+            let weight = idf.get(&feature)?;
+            Some((feature, (1.0 + count.ln().max(0.0)) * weight))
+        })
+        .collect();
+    let norm = vector.values().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        vector.values_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glossary::Glossary;
+    use crate::spelling::Vocabulary;
+    use crate::synonyms::Synonyms;
+    use crate::syntax::QuerySyntax;
+
+    fn query(text: &str) -> PreparedQuery {
+        PreparedQuery::new(
+            QuerySyntax::parse(text),
+            &Glossary::default(),
+            &Synonyms::default(),
+            &Vocabulary::default(),
+        )
+    }
+
+    #[test]
+    fn test_similarity_is_bounded_cosine() {
+        let skills = vec![
+            Skill::for_test("auth-flows").body("authentication tokens sessions"),
+            Skill::for_test("layout").body("grid flexbox columns"),
+        ];
+        let index = SemanticIndex::build(&skills);
+        let q = index.embed_query(&query("authentication sessions"));
+        let related = index.similarity(&q, &skills[0]);
+        let unrelated = index.similarity(&q, &skills[1]);
+        assert!(related > unrelated);
+        assert!((0.0..=1.0 + 1e-9).contains(&related));
+    }
+
+    #[test]
+    fn test_subword_features_match_without_shared_words() {
+        let skills = vec![
+            Skill::for_test("identity").body("authentication"),
+            Skill::for_test("layout").body("grid"),
+        ];
+        let index = SemanticIndex::build(&skills);
+        let q = index.embed_query(&query("auth"));
+        assert!(index.similarity(&q, &skills[0]) > 0.0);
+        assert_eq!(index.similarity(&q, &skills[1]), 0.0);
+    }
+
+    #[test]
+    fn test_rerank_adds_similarity_without_lowering_lexical_scores() {
+        let skills = vec![
+            Skill::for_test("auth-flows").body("authentication"),
+            Skill::for_test("layout").body("grid"),
+        ];
+        let index = SemanticIndex::build(&skills);
+        let mut ranked = vec![
+            (10, &skills[1], SkillSignals::default()),
+            (8, &skills[0], SkillSignals::default()),
+        ];
+        rerank(&mut ranked, &index, &query("authentication"), 0.5, 40);
+        assert_eq!(ranked[0].1.name, "auth-flows");
+        assert!(ranked[0].2.semantic_similarity > 0);
+        assert_eq!((ranked[1].0, ranked[1].1.name.as_str()), (10, "layout"));
+    }
+}
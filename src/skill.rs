@@ -3,25 +3,79 @@
 use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
+use std::sync::LazyLock;
 
 use anyhow::{Context, Result};
 use glob::glob;
 use include_dir::Dir;
+use rust_stemmers::{Algorithm, Stemmer};
 use serde::Deserialize;
 
+use crate::markdown::{parse_blocks, Block};
+
 /// A skill playbook loaded from a SKILL.md file.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Skill {
     pub name: String,
     pub summary: String,
     pub keywords: Vec<String>,
+    /// Alternate names the skill should also be routed by
+    pub aliases: Vec<String>,
+    /// Situations in which the skill should not be picked
+    pub avoid_when: Vec<String>,
+    /// Example task descriptions used to train the query classifier
+    pub examples: Vec<String>,
+    /// Project marker files or globs (e.g. `Cargo.toml`, `*.xcodeproj`)
+    pub applies_to: Vec<String>,
     pub doc: String,
     pub extra_docs: Vec<ExtraDoc>,
     // Pre-computed tokens for faster matching
     pub name_tokens: Vec<String>,
     pub summary_tokens: Vec<String>,
     pub tag_tokens: Vec<String>,
+    pub alias_tokens: Vec<String>,
+    /// Tokens of each `avoid_when` trigger, one list per entry
+    pub avoid_tokens: Vec<Vec<String>>,
+    /// Tokens of the whole body; the fields below split it by structure
     pub body_tokens: Vec<String>,
+    /// Tokens of markdown headings in the body
+    pub heading_tokens: Vec<String>,
+    /// Tokens of body paragraphs and list items
+    pub prose_tokens: Vec<String>,
+    /// Tokens of fenced code blocks, including their language tags
+    pub code_tokens: Vec<String>,
+    /// Bigram and trigram shingles from the name, summary, tags, aliases and body
+    pub shingles: Vec<String>,
+}
+
+/// Structural part of a skill body, scored as a field of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodySection {
+    Headings,
+    Prose,
+    Code,
+}
+
+impl BodySection {
+    pub const ALL: [BodySection; 3] = [Self::Headings, Self::Prose, Self::Code];
+
+    /// Field name used in explanations.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Headings => "heading",
+            Self::Prose => "prose",
+            Self::Code => "code",
+        }
+    }
+
+    /// The skill's tokens for this section.
+    pub fn tokens(self, skill: &Skill) -> &[String] {
+        match self {
+            Self::Headings => &skill.heading_tokens,
+            Self::Prose => &skill.prose_tokens,
+            Self::Code => &skill.code_tokens,
+        }
+    }
 }
 
 /// Additional documentation file associated with a skill.
@@ -29,6 +83,20 @@ pub struct Skill {
 pub struct ExtraDoc {
     pub name: String,
     pub contents: String,
+    // Pre-computed tokens for scoring extra docs
+    pub tokens: Vec<String>,
+}
+
+impl ExtraDoc {
+    /// Create an extra doc and tokenize its contents.
+    pub fn new(name: String, contents: String) -> Self {
+        let tokens = normalized_tokens(&contents);
+        Self {
+            name,
+            contents,
+            tokens,
+        }
+    }
 }
 
 /// YAML frontmatter structure for skill files.
@@ -38,10 +106,28 @@ pub struct SkillFrontmatter {
     pub description: String,
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default, alias = "anti_tags")]
+    pub avoid_when: Vec<String>,
+    #[serde(default)]
+    pub examples: Vec<String>,
+    #[serde(default)]
+    pub applies_to: Vec<String>,
 }
 
+/// Shared English Snowball stemmer used by the token pipeline.
+static STEMMER: LazyLock<Stemmer> = LazyLock::new(|| Stemmer::create(Algorithm::English));
+
 /// Normalize text into tokens for matching.
-/// Filters stopwords and splits on non-alphanumeric characters.
+/// Filters stopwords, splits on non-alphanumeric characters and stems each
+/// word so inflections ("designing", "designs") share a token with "design".
+/// Words of three characters or fewer are kept as is, so short terms such as
+/// "ios" do not stem into unrelated ones ("io").
+/// Identifiers such as `StoreKit2` or `async_trait` yield both the compound
+/// token and their parts, so "store kit" and "StoreKit" match each other.
+/// Runs of CJK characters, which have no spaces between words, become
+/// overlapping character bigrams ("プッシュ通知" -> "プッ", "ッシ", ...).
 pub fn normalized_tokens(text: &str) -> Vec<String> {
     let stopwords: HashSet<&'static str> = [
         "the", "a", "an", "to", "and", "or", "for", "into", "with", "when", "of", "use", "be",
@@ -50,19 +136,120 @@ pub fn normalized_tokens(text: &str) -> Vec<String> {
     .into_iter()
     .collect();
 
-    text.to_lowercase()
-        .split(|c: char| !c.is_alphanumeric())
-        .filter_map(|w| {
-            let word = w.trim();
-            if word.is_empty() || stopwords.contains(word) {
-                None
-            } else {
-                Some(word.to_string())
+    let mut tokens = Vec::new();
+    let mut push = |word: &str| {
+        if word.is_empty() || stopwords.contains(word) {
+            return;
+        }
+        if word.chars().count() <= 3 {
+            tokens.push(word.to_string());
+        } else {
+            tokens.push(STEMMER.stem(word).into_owned());
+        }
+    };
+
+    for segment in text.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        for (word, cjk) in cjk_runs(segment) {
+            if cjk {
+                for bigram in cjk_bigrams(word) {
+                    push(&bigram);
+                }
+                continue;
             }
-        })
+            let parts = identifier_parts(word);
+            if parts.len() > 1 {
+                push(&word.replace('_', "").to_lowercase());
+                for part in parts.iter().filter(|p| p.chars().count() > 2) {
+                    push(&part.to_lowercase());
+                }
+            } else if let Some(part) = parts.first() {
+                push(&part.to_lowercase());
+            }
+        }
+    }
+    tokens
+}
+
+/// Whether a character belongs to a script written without word spaces:
+/// CJK ideographs, hiragana, katakana or hangul.
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'   // hiragana, katakana
+        | '\u{31F0}'..='\u{31FF}' // katakana phonetic extensions
+        | '\u{3400}'..='\u{4DBF}' // CJK extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+        | '\u{AC00}'..='\u{D7AF}' // hangul syllables
+        | '\u{F900}'..='\u{FAFF}' // CJK compatibility ideographs
+        | '\u{FF66}'..='\u{FF9F}' // halfwidth katakana
+    )
+}
+
+/// Split a word into maximal CJK and non-CJK runs, flagging the CJK ones.
+fn cjk_runs(word: &str) -> Vec<(&str, bool)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut current = None;
+    for (idx, c) in word.char_indices() {
+        let cjk = is_cjk(c);
+        if current.is_some_and(|flag| flag != cjk) {
+            runs.push((&word[start..idx], !cjk));
+            start = idx;
+        }
+        current = Some(cjk);
+    }
+    if let Some(cjk) = current {
+        runs.push((&word[start..], cjk));
+    }
+    runs
+}
+
+/// Overlapping character bigrams of a CJK run. A lone character is kept as
+/// is, except hiragana, which on its own is a grammatical particle.
+fn cjk_bigrams(run: &str) -> Vec<String> {
+    let chars: Vec<char> = run.chars().collect();
+    match chars.as_slice() {
+        [c] if ('\u{3040}'..='\u{309F}').contains(c) => Vec::new(),
+        [c] => vec![c.to_string()],
+        _ => chars.windows(2).map(|w| w.iter().collect()).collect(),
+    }
+}
+
+/// Build bigram and trigram shingles from consecutive tokens so multi-word
+/// concepts ("push notifications", "code review") can be matched as phrases.
+pub fn shingles(tokens: &[String]) -> Vec<String> {
+    (2..=3)
+        .flat_map(|n| tokens.windows(n).map(|w| w.join(" ")))
         .collect()
 }
 
+/// Split an identifier on snake_case, camelCase/PascalCase and letter/digit
+/// boundaries ("NavigationStack" -> ["Navigation", "Stack"],
+/// "StoreKit2" -> ["Store", "Kit", "2"]).
+fn identifier_parts(word: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    for segment in word.split('_').filter(|s| !s.is_empty()) {
+        let chars: Vec<(usize, char)> = segment.char_indices().collect();
+        let mut start = 0;
+        for (i, window) in chars.windows(2).enumerate() {
+            let (prev, (idx, cur)) = (window[0].1, window[1]);
+            let next = chars.get(i + 2).map(|&(_, c)| c);
+            // A single leading lowercase letter is a prefix, not a word:
+            // "iOS" and "eBay" stay whole.
+            let boundary = (prev.is_lowercase() && cur.is_uppercase() && i > 0)
+                || (prev.is_uppercase()
+                    && cur.is_uppercase()
+                    && next.is_some_and(char::is_lowercase))
+                || prev.is_numeric() != cur.is_numeric();
+            if boundary {
+                parts.push(&segment[start..idx]);
+                start = idx;
+            }
+        }
+        parts.push(&segment[start..]);
+    }
+    parts
+}
+
 /// Parse a skill from raw markdown text with YAML frontmatter.
 pub fn parse_skill(raw_text: &str, origin: String, extra_docs: Vec<ExtraDoc>) -> Result<Option<Skill>> {
     // Expect frontmatter delimited by lines starting with ---
@@ -123,21 +310,76 @@ pub fn parse_skill(raw_text: &str, origin: String, extra_docs: Vec<ExtraDoc>) ->
         .iter()
         .flat_map(|k| normalized_tokens(k))
         .collect();
+    let alias_tokens: Vec<String> = frontmatter
+        .aliases
+        .iter()
+        .flat_map(|a| normalized_tokens(a))
+        .collect();
+    let avoid_tokens: Vec<Vec<String>> = frontmatter
+        .avoid_when
+        .iter()
+        .map(|a| normalized_tokens(a))
+        .filter(|tokens| !tokens.is_empty())
+        .collect();
     let body_tokens = normalized_tokens(&doc);
+    let (heading_tokens, prose_tokens, code_tokens) = section_tokens(&doc);
+
+    // Shingles never span field boundaries
+    let mut skill_shingles: Vec<String> = [&name_tokens, &summary_tokens, &body_tokens]
+        .into_iter()
+        .flat_map(|tokens| shingles(tokens))
+        .chain(
+            frontmatter
+                .tags
+                .iter()
+                .chain(&frontmatter.aliases)
+                .flat_map(|t| shingles(&normalized_tokens(t))),
+        )
+        .collect();
+    skill_shingles.sort();
+    skill_shingles.dedup();
 
     Ok(Some(Skill {
         name: frontmatter.name,
         summary: frontmatter.description,
         keywords: frontmatter.tags,
+        aliases: frontmatter.aliases,
+        avoid_when: frontmatter.avoid_when,
+        examples: frontmatter.examples,
+        applies_to: frontmatter.applies_to,
         doc,
         extra_docs,
         name_tokens,
         summary_tokens,
         tag_tokens,
+        alias_tokens,
+        avoid_tokens,
         body_tokens,
+        heading_tokens,
+        prose_tokens,
+        code_tokens,
+        shingles: skill_shingles,
     }))
 }
 
+/// Tokenize a markdown body into heading, prose and code tokens.
+fn section_tokens(doc: &str) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let (mut headings, mut prose, mut code) = (Vec::new(), Vec::new(), Vec::new());
+    for block in parse_blocks(doc) {
+        match block {
+            Block::Heading { text, .. } => headings.extend(normalized_tokens(&text)),
+            Block::Paragraph(text) | Block::ListItem(text) => {
+                prose.extend(normalized_tokens(&text))
+            }
+            Block::Code { lang, text } => {
+                code.extend(lang.iter().flat_map(|l| normalized_tokens(l)));
+                code.extend(normalized_tokens(&text));
+            }
+        }
+    }
+    (headings, prose, code)
+}
+
 /// Load extra documentation files from a skill folder (recursive).
 pub fn load_extra_docs_fs(folder: &Path, skill_path: &Path) -> Result<Vec<ExtraDoc>> {
     let mut extra_docs = Vec::new();
@@ -174,49 +416,60 @@ pub fn load_extra_docs_fs(folder: &Path, skill_path: &Path) -> Result<Vec<ExtraD
                     .map(|n| n.to_string_lossy().to_string())
                     .unwrap_or_else(|| "extra.md".into())
             });
-        extra_docs.push(ExtraDoc {
-            name: relative_name,
-            contents,
-        });
+        extra_docs.push(ExtraDoc::new(relative_name, contents));
     }
     extra_docs.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(extra_docs)
 }
 
 /// Load embedded skills from an include_dir directory.
+/// Extra docs are collected recursively with paths relative to the skill
+/// folder, matching `load_extra_docs_fs`.
 pub fn load_embedded_skills(dir: &Dir) -> Result<Vec<Skill>> {
-    fn walk(d: &Dir, skills: &mut Vec<Skill>) -> Result<()> {
-        let mut skill_md = None;
-        let mut extras: Vec<ExtraDoc> = Vec::new();
+    fn is_skill_md(file: &include_dir::File) -> bool {
+        file.path()
+            .file_name()
+            .map(|n| n.to_string_lossy().eq_ignore_ascii_case("SKILL.md"))
+            .unwrap_or(false)
+    }
 
+    fn collect_extras(skill_dir: &Dir, d: &Dir, extras: &mut Vec<ExtraDoc>) {
         for file in d.files() {
-            let name = file
-                .path()
-                .file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_default();
-            if name.eq_ignore_ascii_case("SKILL.md") {
-                skill_md = Some(file);
-            } else if name.to_lowercase().ends_with(".md") {
-                if let Some(contents) = file.contents_utf8() {
-                    extras.push(ExtraDoc {
-                        name,
-                        contents: contents.to_string(),
-                    });
-                }
+            let path = file.path();
+            let is_md = path
+                .extension()
+                .map(|e| e.eq_ignore_ascii_case("md"))
+                .unwrap_or(false);
+            if is_md
+                && !is_skill_md(file)
+                && let Some(contents) = file.contents_utf8()
+            {
+                let name = path
+                    .strip_prefix(skill_dir.path())
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .to_string();
+                extras.push(ExtraDoc::new(name, contents.to_string()));
             }
         }
+        for child in d.dirs() {
+            collect_extras(skill_dir, child, extras);
+        }
+    }
 
-        if let Some(skill_file) = skill_md {
-            if let Some(contents) = skill_file.contents_utf8() {
-                extras.sort_by(|a, b| a.name.cmp(&b.name));
-                if let Some(skill) = parse_skill(
-                    contents,
-                    format!("embedded:{}", skill_file.path().display()),
-                    extras,
-                )? {
-                    skills.push(skill);
-                }
+    fn walk(d: &Dir, skills: &mut Vec<Skill>) -> Result<()> {
+        if let Some(skill_file) = d.files().find(|f| is_skill_md(f))
+            && let Some(contents) = skill_file.contents_utf8()
+        {
+            let mut extras = Vec::new();
+            collect_extras(d, d, &mut extras);
+            extras.sort_by(|a, b| a.name.cmp(&b.name));
+            if let Some(skill) = parse_skill(
+                contents,
+                format!("embedded:{}", skill_file.path().display()),
+                extras,
+            )? {
+                skills.push(skill);
             }
         }
 
@@ -233,9 +486,148 @@ pub fn load_embedded_skills(dir: &Dir) -> Result<Vec<Skill>> {
 }
 
 /// Find a skill by name (case-insensitive, supports partial match).
+/// Falls back to an exact match against declared aliases.
 pub fn find_skill<'a>(skills: &'a [Skill], name: &str) -> Option<&'a Skill> {
     let needle = name.to_lowercase();
     skills
         .iter()
         .find(|s| s.name.to_lowercase() == needle || s.name.to_lowercase().contains(&needle))
+        .or_else(|| {
+            skills
+                .iter()
+                .find(|s| s.aliases.iter().any(|a| a.to_lowercase() == needle))
+        })
+}
+
+/// Find a skill whose name or one of whose aliases equals `name`
+/// (case-insensitive). Unlike [`find_skill`], partial names never match.
+pub fn find_skill_exact<'a>(skills: &'a [Skill], name: &str) -> Option<&'a Skill> {
+    let needle = name.to_lowercase();
+    skills
+        .iter()
+        .find(|s| s.name.to_lowercase() == needle)
+        .or_else(|| {
+            skills
+                .iter()
+                .find(|s| s.aliases.iter().any(|a| a.to_lowercase() == needle))
+        })
+}
+
+/// Test fixtures: each setter fills a field and its tokens the way
+/// [`parse_skill`] would, e.g. `Skill::for_test("ios").body("...")`.
+#[cfg(test)]
+impl Skill {
+    pub fn for_test(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            name_tokens: normalized_tokens(name),
+            ..Default::default()
+        }
+    }
+
+    pub fn summary(mut self, summary: &str) -> Self {
+        self.summary_tokens = normalized_tokens(summary);
+        self.summary = summary.to_string();
+        self
+    }
+
+    pub fn body(mut self, body: &str) -> Self {
+        self.body_tokens = normalized_tokens(body);
+        (self.heading_tokens, self.prose_tokens, self.code_tokens) = section_tokens(body);
+        self.doc = body.to_string();
+        self
+    }
+
+    pub fn tags(mut self, tags: &[&str]) -> Self {
+        self.tag_tokens = tags.iter().flat_map(|t| normalized_tokens(t)).collect();
+        self.keywords = tags.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    pub fn aliases(mut self, aliases: &[&str]) -> Self {
+        self.alias_tokens = aliases.iter().flat_map(|a| normalized_tokens(a)).collect();
+        self.aliases = aliases.iter().map(|a| a.to_string()).collect();
+        self
+    }
+
+    pub fn examples(mut self, examples: &[&str]) -> Self {
+        self.examples = examples.iter().map(|e| e.to_string()).collect();
+        self
+    }
+
+    pub fn applies_to(mut self, markers: &[&str]) -> Self {
+        self.applies_to = markers.iter().map(|m| m.to_string()).collect();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_parts_split_case_and_digit_boundaries() {
+        assert_eq!(identifier_parts("NavigationStack"), vec!["Navigation", "Stack"]);
+        assert_eq!(identifier_parts("SwiftUIKit"), vec!["Swift", "UI", "Kit"]);
+        assert_eq!(identifier_parts("StoreKit2"), vec!["Store", "Kit", "2"]);
+        assert_eq!(identifier_parts("async_trait"), vec!["async", "trait"]);
+        assert_eq!(identifier_parts("storekit"), vec!["storekit"]);
+        assert_eq!(identifier_parts("iOS"), vec!["iOS"]);
+        assert_eq!(identifier_parts("eBay"), vec!["eBay"]);
+        assert_eq!(identifier_parts("macOS"), vec!["mac", "OS"]);
+    }
+
+    #[test]
+    fn test_shingles_cover_bigrams_and_trigrams() {
+        let tokens = normalized_tokens("app store review guidelines");
+        let grams = shingles(&tokens);
+        assert!(grams.contains(&"app store".to_string()));
+        assert!(grams.contains(&"store review guidelin".to_string()));
+        assert_eq!(grams.len(), 3 + 2);
+    }
+
+    #[test]
+    fn test_normalized_tokens_keep_compound_and_parts() {
+        let tokens = normalized_tokens("StoreKit");
+        assert!(tokens.contains(&"storekit".to_string()));
+        assert!(tokens.contains(&"store".to_string()));
+        assert!(tokens.contains(&"kit".to_string()));
+
+        let snake = normalized_tokens("async_trait");
+        assert!(snake.contains(&"asynctrait".to_string()));
+        assert!(snake.contains(&"async".to_string()));
+        assert!(snake.contains(&"trait".to_string()));
+    }
+
+    #[test]
+    fn test_platform_names_do_not_emit_short_parts() {
+        assert_eq!(normalized_tokens("iOS"), vec!["ios"]);
+        assert_eq!(normalized_tokens("tvOS"), vec!["tvos"]);
+    }
+
+    #[test]
+    fn test_short_words_are_not_stemmed() {
+        assert_eq!(normalized_tokens("ios io"), vec!["ios", "io"]);
+        assert_eq!(normalized_tokens("designs"), vec!["design"]);
+    }
+
+    #[test]
+    fn test_body_is_split_into_heading_prose_and_code_tokens() {
+        let raw = "---\nname: demo\ndescription: Demo\n---\n## Overview\nLayout tips.\n- grids\n```swift\nlet stack = VStack()\n```\n";
+        let skill = parse_skill(raw, "demo".into(), Vec::new()).unwrap().unwrap();
+        assert_eq!(skill.heading_tokens, normalized_tokens("Overview"));
+        assert_eq!(skill.prose_tokens, normalized_tokens("Layout tips. grids"));
+        assert_eq!(skill.code_tokens, normalized_tokens("swift let stack = VStack()"));
+        assert!(skill.body_tokens.contains(&"overview".to_string()));
+    }
+
+    #[test]
+    fn test_cjk_runs_become_bigrams_and_particles_drop() {
+        assert_eq!(
+            normalized_tokens("プッシュ通知を設定"),
+            vec!["プッ", "ッシ", "シュ", "ュ通", "通知", "知を", "を設", "設定"]
+        );
+        assert_eq!(normalized_tokens("Rustの エラー"), vec!["rust", "エラ", "ラー"]);
+        assert_eq!(normalized_tokens("の 字"), vec!["字"]);
+    }
 }
@@ -0,0 +1,152 @@
+//! Typo correction of query words against the skill corpus vocabulary.
+
+use std::collections::{HashMap, HashSet};
+
+use strsim::damerau_levenshtein;
+
+use crate::skill::{normalized_tokens, Skill};
+
+/// Shortest word considered for correction.
+const MIN_CORRECTABLE_LEN: usize = 4;
+
+/// Surface words and stems seen across all loaded skills.
+#[derive(Debug, Clone, Default)]
+pub struct Vocabulary {
+    /// Lowercased word -> number of skills containing it.
+    words: HashMap<String, usize>,
+    /// Stemmed tokens of every known word, so inflections count as known.
+    stems: HashSet<String>,
+}
+
+impl Vocabulary {
+    /// Collect words from every skill's frontmatter, body and extra docs.
+    pub fn build(skills: &[Skill]) -> Self {
+        let mut words: HashMap<String, usize> = HashMap::new();
+        for skill in skills {
+            let mut seen = HashSet::new();
+            let texts = [skill.name.as_str(), skill.summary.as_str(), skill.doc.as_str()]
+                .into_iter()
+                .chain(skill.keywords.iter().map(String::as_str))
+                .chain(skill.aliases.iter().map(String::as_str))
+                .chain(skill.extra_docs.iter().map(|e| e.contents.as_str()));
+            for text in texts {
+                for word in split_words(text) {
+                    if word.chars().count() > 1 && seen.insert(word.clone()) {
+                        *words.entry(word).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let stems = words.keys().flat_map(|w| normalized_tokens(w)).collect();
+        Self { words, stems }
+    }
+
+    /// Whether a lowercased word, or every stem it normalizes to, is known.
+    pub fn is_known(&self, word: &str) -> bool {
+        self.words.contains_key(word)
+            || normalized_tokens(word)
+                .iter()
+                .all(|stem| self.stems.contains(stem))
+    }
+
+    /// Suggest the closest known word for an unknown one.
+    /// Allows one edit for words up to eight characters and two beyond that,
+    /// so short real words missing from the corpus are not "corrected";
+    /// ties prefer words found in more skills.
+    pub fn correct(&self, word: &str) -> Option<&str> {
+        let len = word.chars().count();
+        if len < MIN_CORRECTABLE_LEN
+            || word.chars().any(|c| c.is_numeric())
+            || self.is_known(word)
+        {
+            return None;
+        }
+        let max_distance = if len <= 8 { 1 } else { 2 };
+
+        self.words
+            .iter()
+            .filter(|(candidate, _)| candidate.chars().count().abs_diff(len) <= max_distance)
+            .map(|(candidate, freq)| (damerau_levenshtein(word, candidate), *freq, candidate))
+            .filter(|(distance, _, _)| *distance <= max_distance)
+            .min_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(b.2)))
+            .map(|(_, _, candidate)| candidate.as_str())
+    }
+
+    /// Correct every unknown word in a query.
+    /// Returns (original, correction) pairs in query order, without repeats.
+    pub fn corrections(&self, query: &str) -> Vec<(String, String)> {
+        let mut seen = HashSet::new();
+        split_words(query)
+            .filter(|word| seen.insert(word.clone()))
+            .filter_map(|word| {
+                let fixed = self.correct(&word)?.to_string();
+                Some((word, fixed))
+            })
+            .collect()
+    }
+}
+
+/// Lowercased alphanumeric words of a text.
+fn split_words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// Rewrite a query with corrected words substituted, for "did you mean" hints.
+pub fn corrected_query(query: &str, corrections: &[(String, String)]) -> String {
+    query
+        .split_whitespace()
+        .map(|piece| {
+            let bare = piece
+                .trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase();
+            corrections
+                .iter()
+                .find(|(original, _)| *original == bare)
+                .map(|(_, fixed)| fixed.clone())
+                .unwrap_or_else(|| piece.to_string())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocabulary() -> Vocabulary {
+        let skill = Skill::for_test("swiftui-guide")
+            .summary("SwiftUI concurrency and accessibility")
+            .body("Design accessible views.");
+        Vocabulary::build(&[skill])
+    }
+
+    #[test]
+    fn test_common_typos_are_corrected() {
+        let vocab = vocabulary();
+        assert_eq!(vocab.correct("swfitui"), Some("swiftui"));
+        assert_eq!(vocab.correct("concurency"), Some("concurrency"));
+        assert_eq!(vocab.correct("acessibility"), Some("accessibility"));
+    }
+
+    #[test]
+    fn test_known_words_and_inflections_are_left_alone() {
+        let vocab = vocabulary();
+        assert_eq!(vocab.correct("swiftui"), None);
+        assert_eq!(vocab.correct("designing"), None);
+        assert_eq!(vocab.correct("zzzzzzzz"), None);
+        assert_eq!(vocab.correct("guidelines"), None);
+    }
+
+    #[test]
+    fn test_corrected_query_substitutes_words() {
+        let vocab = vocabulary();
+        let fixes = vocab.corrections("Swfitui concurency tips");
+        assert_eq!(
+            corrected_query("Swfitui concurency tips", &fixes),
+            "swiftui concurrency tips"
+        );
+    }
+}
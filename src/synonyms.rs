@@ -0,0 +1,93 @@
+//! Query expansion through project-wide synonym groups.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::skill::normalized_tokens;
+
+/// Normalized synonym lookup built from the `[synonyms]` config table.
+/// Each entry forms a symmetric group: `ios = ["iphone"]` lets either term
+/// expand to the other. Multi-word terms stay phrases: `ux = ["user
+/// experience"]` expands "ux" to both words, but only the whole phrase
+/// expands back to "ux".
+#[derive(Debug, Clone, Default)]
+pub struct Synonyms {
+    /// Normalized phrase (tokens joined by spaces) -> equivalent phrases.
+    groups: HashMap<String, BTreeSet<String>>,
+    /// Token count of the longest phrase in any group.
+    longest: usize,
+}
+
+impl Synonyms {
+    /// Build a lookup from raw config entries (term -> equivalent terms).
+    pub fn from_table(table: &HashMap<String, Vec<String>>) -> Self {
+        let mut groups: HashMap<String, BTreeSet<String>> = HashMap::new();
+        let mut longest = 0;
+        for (term, equivalents) in table {
+            let phrases: Vec<Vec<String>> = std::iter::once(term)
+                .chain(equivalents)
+                .map(|t| normalized_tokens(t))
+                .filter(|tokens| !tokens.is_empty())
+                .collect();
+            longest = phrases.iter().map(Vec::len).fold(longest, usize::max);
+            let group: BTreeSet<String> = phrases.iter().map(|p| p.join(" ")).collect();
+            for phrase in &group {
+                groups
+                    .entry(phrase.clone())
+                    .or_default()
+                    .extend(group.iter().filter(|p| *p != phrase).cloned());
+            }
+        }
+        Self { groups, longest }
+    }
+
+    /// Return tokens reachable through synonym groups that are not already
+    /// part of the query. A multi-word term only matches when all its words
+    /// appear contiguously in the query, like a shingle.
+    pub fn expand(&self, query_tokens: &[String]) -> Vec<String> {
+        let mut expanded = BTreeSet::new();
+        for n in 1..=self.longest {
+            for window in query_tokens.windows(n) {
+                if let Some(group) = self.groups.get(&window.join(" ")) {
+                    expanded.extend(
+                        group
+                            .iter()
+                            .flat_map(|p| p.split(' '))
+                            .filter(|t| !query_tokens.iter().any(|q| q == t))
+                            .map(str::to_string),
+                    );
+                }
+            }
+        }
+        expanded.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expansion_is_symmetric() {
+        let table = HashMap::from([("ios".to_string(), vec!["iphone".to_string()])]);
+        let synonyms = Synonyms::from_table(&table);
+        assert_eq!(synonyms.expand(&normalized_tokens("ios")), normalized_tokens("iphone"));
+        assert_eq!(synonyms.expand(&normalized_tokens("iphone")), normalized_tokens("ios"));
+    }
+
+    #[test]
+    fn test_expansion_skips_tokens_already_in_query() {
+        let table = HashMap::from([("perf".to_string(), vec!["performance".to_string()])]);
+        let synonyms = Synonyms::from_table(&table);
+        assert!(synonyms.expand(&normalized_tokens("perf performance")).is_empty());
+    }
+
+    #[test]
+    fn test_multi_word_synonyms_match_as_phrases() {
+        let table = HashMap::from([("ux".to_string(), vec!["user experience".to_string()])]);
+        let synonyms = Synonyms::from_table(&table);
+        let expand = |query: &str| synonyms.expand(&normalized_tokens(query));
+        assert_eq!(expand("ux review"), normalized_tokens("experience user"));
+        assert_eq!(expand("improve the user experience"), normalized_tokens("ux"));
+        assert!(expand("user settings experience").is_empty());
+    }
+}
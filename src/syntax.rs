@@ -0,0 +1,280 @@
+//! Query syntax for `pick` and `search`.
+//!
+//! Besides free text, a query may contain `"exact phrases"`, `-exclusions`
+//! (also `-"quoted phrases"`) and field-scoped terms `name:ios`, `tag:swift`
+//! and `body:xcodebuild`. Phrases and scoped terms still count as query text
+//! for scoring; all of them also restrict which skills are eligible.
+
+use crate::skill::{normalized_tokens, Skill};
+
+/// Skill field a scoped term must appear in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryField {
+    Name,
+    Tag,
+    Body,
+}
+
+impl QueryField {
+    fn parse(prefix: &str) -> Option<Self> {
+        match prefix.to_lowercase().as_str() {
+            "name" => Some(Self::Name),
+            "tag" => Some(Self::Tag),
+            "body" => Some(Self::Body),
+            _ => None,
+        }
+    }
+
+    /// Token lists searched for this field; the body includes the skill's
+    /// extra reference docs.
+    fn tokens(self, skill: &Skill) -> Vec<&[String]> {
+        match self {
+            Self::Name => vec![&skill.name_tokens],
+            Self::Tag => vec![&skill.tag_tokens],
+            Self::Body => std::iter::once(skill.body_tokens.as_slice())
+                .chain(skill.extra_docs.iter().map(|e| e.tokens.as_slice()))
+                .collect(),
+        }
+    }
+}
+
+/// A term or phrase from the query, raw and normalized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    /// Lowercased text as written, for substring search.
+    pub text: String,
+    /// Normalized tokens, matched as a contiguous sequence.
+    pub tokens: Vec<String>,
+}
+
+impl Term {
+    fn new(text: &str) -> Self {
+        Self {
+            text: text.to_lowercase(),
+            tokens: normalized_tokens(text),
+        }
+    }
+}
+
+/// A query split into free text and structured constraints.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QuerySyntax {
+    /// Text to score: free words, phrases and scoped terms, without operators.
+    pub text: String,
+    /// Words outside any phrase, exclusion or field prefix.
+    pub free: Vec<String>,
+    /// `"quoted phrases"` that must appear verbatim.
+    pub phrases: Vec<Term>,
+    /// `-terms` whose skills are dropped.
+    pub excluded: Vec<Term>,
+    /// `field:term` constraints.
+    pub scoped: Vec<(QueryField, Term)>,
+}
+
+impl QuerySyntax {
+    /// Parse a raw query. Unknown `prefix:` words, a lone `-` and unbalanced
+    /// quotes are kept as plain text.
+    pub fn parse(query: &str) -> Self {
+        let mut syntax = Self::default();
+        let mut text = Vec::new();
+        for (word, quoted) in split_words(query) {
+            if quoted {
+                syntax.phrases.push(Term::new(&word));
+                text.push(word);
+            } else if let Some(rest) = word.strip_prefix('-')
+                && !rest.is_empty()
+                && !rest.starts_with('-')
+            {
+                syntax.excluded.push(Term::new(rest));
+            } else if let Some((prefix, term)) = word.split_once(':')
+                && let Some(field) = QueryField::parse(prefix)
+                && !term.is_empty()
+            {
+                syntax.scoped.push((field, Term::new(term)));
+                text.push(term.to_string());
+            } else {
+                syntax.free.push(word.clone());
+                text.push(word);
+            }
+        }
+        syntax.text = text.join(" ");
+        syntax
+    }
+
+    /// Treat a query as free text only, e.g. an issue body or diff where
+    /// quotes, dashes and colons carry no query meaning.
+    pub fn plain(query: &str) -> Self {
+        let free: Vec<String> = query.split_whitespace().map(str::to_string).collect();
+        Self {
+            text: free.join(" "),
+            free,
+            ..Self::default()
+        }
+    }
+
+    /// Rewrite the scored text and every phrase, exclusion and scoped term
+    /// through `translate` (e.g. a glossary), collapsing whitespace.
+    pub fn translate(&mut self, translate: impl Fn(&str) -> String) {
+        let rewrite = |text: &str| {
+            translate(text)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        self.text = rewrite(&self.text);
+        let terms = self
+            .phrases
+            .iter_mut()
+            .chain(self.excluded.iter_mut())
+            .chain(self.scoped.iter_mut().map(|(_, term)| term));
+        for term in terms {
+            *term = Term::new(&rewrite(&term.text));
+        }
+    }
+
+    /// Whether the query uses no operators at all.
+    pub fn is_plain(&self) -> bool {
+        self.phrases.is_empty() && self.excluded.is_empty() && self.scoped.is_empty()
+    }
+
+    /// Whether a skill satisfies every phrase, exclusion and scoped term.
+    /// Phrases and exclusions are looked up in the name, summary, tags,
+    /// aliases, body and extra reference docs.
+    pub fn admits(&self, skill: &Skill) -> bool {
+        let fields: Vec<&[String]> = [
+            &skill.name_tokens,
+            &skill.summary_tokens,
+            &skill.tag_tokens,
+            &skill.alias_tokens,
+        ]
+        .into_iter()
+        .map(Vec::as_slice)
+        .chain(QueryField::Body.tokens(skill))
+        .collect();
+        let anywhere = |term: &Term| fields.iter().any(|f| contains_sequence(f, &term.tokens));
+        self.phrases.iter().all(anywhere)
+            && !self.excluded.iter().any(anywhere)
+            && self.scoped.iter().all(|(field, term)| {
+                field
+                    .tokens(skill)
+                    .iter()
+                    .any(|f| contains_sequence(f, &term.tokens))
+            })
+    }
+
+    /// Human-readable list of the constraints, for `--explain`.
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        parts.extend(self.phrases.iter().map(|p| format!("\"{}\"", p.text)));
+        parts.extend(self.excluded.iter().map(|e| format!("-{}", e.text)));
+        parts.extend(self.scoped.iter().map(|(field, term)| {
+            let prefix = match field {
+                QueryField::Name => "name",
+                QueryField::Tag => "tag",
+                QueryField::Body => "body",
+            };
+            format!("{}:{}", prefix, term.text)
+        }));
+        parts.join(", ")
+    }
+}
+
+/// Whether `needle` occurs as a contiguous run in `haystack`. Terms without
+/// any token (e.g. only stopwords) match nothing.
+pub fn contains_sequence(haystack: &[String], needle: &[String]) -> bool {
+    !needle.is_empty() && haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+/// Split on whitespace, keeping `"quoted text"` together. Returns each word
+/// with whether it was a quoted phrase; a quote after `-` or `field:` is
+/// folded into that word (`-"a b"` yields `-a b`, unquoted).
+fn split_words(query: &str) -> Vec<(String, bool)> {
+    let mut words = Vec::new();
+    let mut rest = query.trim_start();
+    while !rest.is_empty() {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let quote = rest[..end].find('"');
+        if let Some(open) = quote
+            && let Some(len) = rest[open + 1..].find('"')
+        {
+            let prefix = &rest[..open];
+            let phrase = &rest[open + 1..open + 1 + len];
+            if prefix.is_empty() {
+                words.push((phrase.to_string(), true));
+            } else {
+                words.push((format!("{}{}", prefix, phrase), false));
+            }
+            rest = rest[open + len + 2..].trim_start();
+        } else {
+            words.push((rest[..end].to_string(), false));
+            rest = rest[end..].trim_start();
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skill::ExtraDoc;
+
+    #[test]
+    fn test_parses_phrases_exclusions_and_fields() {
+        let syntax = QuerySyntax::parse(r#"set up "push notifications" -macos tag:swift -"app clips" url:x"#);
+        assert_eq!(syntax.text, "set up push notifications swift url:x");
+        assert_eq!(syntax.free, vec!["set", "up", "url:x"]);
+        assert_eq!(syntax.phrases, vec![Term::new("push notifications")]);
+        assert_eq!(syntax.excluded, vec![Term::new("macos"), Term::new("app clips")]);
+        assert_eq!(syntax.scoped, vec![(QueryField::Tag, Term::new("swift"))]);
+    }
+
+    #[test]
+    fn test_plain_text_and_stray_operators_pass_through() {
+        let syntax = QuerySyntax::parse(r#"fix - the "broken quote"#);
+        assert!(syntax.is_plain());
+        assert_eq!(syntax.text, r#"fix - the "broken quote"#);
+    }
+
+    #[test]
+    fn test_admits_applies_every_constraint() {
+        let ios = Skill::for_test("ios-notifications")
+            .tags(&["swift", "apns"])
+            .body("Send push notifications to iPhone");
+        let mac = Skill::for_test("macos-menus")
+            .tags(&["swift", "appkit"])
+            .body("Build push menus for macOS notifications");
+
+        let phrase = QuerySyntax::parse(r#""push notifications""#);
+        assert!(phrase.admits(&ios));
+        assert!(!phrase.admits(&mac));
+
+        let excluded = QuerySyntax::parse("notifications -macos");
+        assert!(excluded.admits(&ios));
+        assert!(!excluded.admits(&mac));
+
+        let scoped = QuerySyntax::parse("name:ios body:iphone tag:swift");
+        assert!(scoped.admits(&ios));
+        assert!(!scoped.admits(&mac));
+        assert!(!QuerySyntax::parse("tag:iphone").admits(&ios));
+    }
+
+    #[test]
+    fn test_extra_docs_count_as_body() {
+        let mut ios = Skill::for_test("ios-notifications")
+            .tags(&["swift"])
+            .body("Send push notifications");
+        ios.extra_docs
+            .push(ExtraDoc::new("references/apns.md".into(), "Rotate the device token".into()));
+
+        assert!(QuerySyntax::parse(r#""device token""#).admits(&ios));
+        assert!(QuerySyntax::parse("body:rotate").admits(&ios));
+        assert!(!QuerySyntax::parse("push -rotate").admits(&ios));
+    }
+
+    #[test]
+    fn test_plain_queries_keep_operators_as_text() {
+        let plain = QuerySyntax::plain("Thread 1: EXC_BAD_ACCESS \"crash\" -O2");
+        assert!(plain.is_plain());
+        assert_eq!(plain.text, "Thread 1: EXC_BAD_ACCESS \"crash\" -O2");
+    }
+}
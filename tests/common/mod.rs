@@ -0,0 +1,23 @@
+//! Helpers shared by integration tests that build skills trees on disk.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+
+/// Write `skills/<folder>/SKILL.md` under `root` with the given frontmatter
+/// and body.
+pub fn write_skill(root: &Path, folder: &str, frontmatter: &str, body: &str) {
+    let dir = root.join("skills").join(folder);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("SKILL.md"), format!("---\n{frontmatter}\n---\n{body}\n")).unwrap();
+}
+
+/// A temporary directory holding a `skills/` tree with one skill per
+/// `(folder, frontmatter, body)` entry.
+pub fn skills_root(skills: &[(&str, &str, &str)]) -> tempfile::TempDir {
+    let root = tempfile::tempdir().unwrap();
+    for (folder, frontmatter, body) in skills {
+        write_skill(root.path(), folder, frontmatter, body);
+    }
+    root
+}
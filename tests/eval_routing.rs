@@ -0,0 +1,103 @@
+mod common;
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::str;
+
+use common::skills_root;
+
+fn fixture() -> tempfile::TempDir {
+    let root = skills_root(&[
+        (
+            "release-notes",
+            "name: release-notes\ndescription: Draft a changelog for a new version",
+            "Group changes by feature and fix.",
+        ),
+        (
+            "db-migrations",
+            "name: db-migrations\ndescription: Evolve the database schema safely",
+            "Write reversible migrations.",
+        ),
+    ]);
+    fs::write(
+        root.path().join("golden.jsonl"),
+        concat!(
+            "{\"query\": \"changelog for version 2\", \"expected\": [\"release-notes\"]}\n",
+            "{\"query\": \"version the database schema\", \"expected\": [\"release-notes\"]}\n",
+        ),
+    )
+    .unwrap();
+    root
+}
+
+#[test]
+fn bundled_golden_set_routes_correctly() {
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.args(["--skills-dir", "skills", "eval", "tests/golden/routing.yaml"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("precision@1    1.000"))
+        .stdout(str::contains("MRR            1.000"));
+}
+
+#[test]
+fn eval_reports_confusions_from_jsonl() {
+    let root = fixture();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .args(["eval", "golden.jsonl"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("precision@1    0.500"))
+        .stdout(str::contains("release-notes -> db-migrations (1)"));
+}
+
+#[test]
+fn eval_fails_below_configured_threshold() {
+    let root = fixture();
+    fs::write(
+        root.path().join(".codex-skills.toml"),
+        "[eval]\nmin_precision_at_1 = 0.9\n",
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .args(["eval", "golden.jsonl"]);
+    cmd.assert()
+        .failure()
+        .stdout(str::contains("FAIL: precision@1 0.500 is below 0.900"));
+}
+
+#[test]
+fn eval_rejects_partial_skill_names() {
+    let root = fixture();
+    fs::write(
+        root.path().join("partial.jsonl"),
+        "{\"query\": \"changelog for version 2\", \"expected\": [\"release\"]}\n",
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .args(["eval", "partial.jsonl"]);
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("expects unknown skill 'release'"));
+
+    fs::write(
+        root.path().join("exact.jsonl"),
+        "{\"query\": \"changelog for version 2\", \"expected\": [\"Release-Notes\"]}\n",
+    )
+    .unwrap();
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .args(["eval", "exact.jsonl"]);
+    cmd.assert().success();
+}
@@ -0,0 +1,69 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use serde_json::Value;
+
+fn batch(args: &[&str], stdin: &str) -> Vec<Value> {
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.args(["--skills-dir", "skills", "pick", "--batch"])
+        .args(args)
+        .write_stdin(stdin);
+    let out = String::from_utf8(cmd.assert().success().get_output().stdout.clone()).unwrap();
+    out.lines()
+        .map(|line| serde_json::from_str(line).expect("each line is JSON"))
+        .collect()
+}
+
+#[test]
+fn batch_reads_plain_and_jsonl_queries_from_stdin() {
+    let results = batch(
+        &["-", "--top", "2"],
+        "rust error handling\n\n{\"id\": \"t-2\", \"query\": \"ios ux improvements\"}\n",
+    );
+    assert_eq!(results.len(), 2);
+
+    assert_eq!(results[0]["id"], 1);
+    assert_eq!(results[0]["results"][0]["skill"], "Rust Best Practices Guide");
+    assert_eq!(results[0]["results"].as_array().unwrap().len(), 2);
+    assert!(results[0]["results"][0]["signals"]["name_hits"].is_u64());
+
+    assert_eq!(results[1]["id"], "t-2");
+    assert_eq!(results[1]["results"][0]["skill"], "ios-ux-design");
+    assert_eq!(results[1]["verdict"], "clear winner");
+}
+
+#[test]
+fn batch_reads_queries_from_a_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("queries.txt");
+    fs::write(&input, "quantum knitting\n").unwrap();
+
+    let results = batch(&[input.to_str().unwrap()], "");
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["verdict"], "nothing fits");
+}
+
+#[test]
+fn batch_conflicts_with_a_positional_query() {
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.args(["--skills-dir", "skills", "pick", "rust", "--batch", "-"]);
+    cmd.assert().failure();
+}
+
+#[test]
+fn batch_reports_malformed_lines_and_keeps_going() {
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.args(["--skills-dir", "skills", "pick", "--batch", "-"])
+        .write_stdin("{\"id\": 1, \"query\": \n rust error handling\n");
+    let assert = cmd.assert().failure();
+    let out = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+    let lines: Vec<Value> = out
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line is JSON"))
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["line"], 1);
+    assert!(lines[0]["error"].as_str().unwrap().contains("Invalid batch entry"));
+    assert_eq!(lines[1]["id"], 2);
+    assert_eq!(lines[1]["results"][0]["skill"], "Rust Best Practices Guide");
+}
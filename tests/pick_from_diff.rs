@@ -0,0 +1,111 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::str;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .current_dir(repo)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {args:?} failed");
+}
+
+fn repo_with_swift_change() -> tempfile::TempDir {
+    let repo = tempfile::tempdir().unwrap();
+    let sources = repo.path().join("Sources");
+    fs::create_dir_all(&sources).unwrap();
+    fs::write(sources.join("FeedLoader.swift"), "import Foundation\n").unwrap();
+    git(repo.path(), &["init", "-q"]);
+    git(repo.path(), &["add", "."]);
+    git(repo.path(), &["commit", "-q", "-m", "init"]);
+    fs::write(
+        sources.join("FeedLoader.swift"),
+        "import Foundation\nfunc load() async {\n    Task { @MainActor in\n        await loader.refresh()\n    }\n}\n",
+    )
+    .unwrap();
+    repo
+}
+
+fn skills_dir() -> String {
+    format!("{}/skills", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn staged_swift_task_diff_routes_to_concurrency_skill() {
+    let repo = repo_with_swift_change();
+    git(repo.path(), &["add", "."]);
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(repo.path())
+        .env("HOME", repo.path())
+        .args(["--skills-dir", &skills_dir(), "pick", "--from-diff", "--top", "2"]);
+    // Both Swift skills covering async/await and actors lead the ranking.
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Query from diff: swift"))
+        .stdout(str::contains(". Swift Concurrency Expert Guide"))
+        .stdout(str::contains(". moai-lang-swift"));
+}
+
+#[test]
+fn diff_against_a_revision_includes_unstaged_changes() {
+    let repo = repo_with_swift_change();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(repo.path())
+        .env("HOME", repo.path())
+        .args(["--skills-dir", &skills_dir(), "pick", "--from-diff=HEAD", "--top", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Task MainActor await"));
+}
+
+#[test]
+fn revision_that_is_also_a_file_name_is_not_ambiguous() {
+    let repo = repo_with_swift_change();
+    fs::write(repo.path().join("HEAD"), "notes\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(repo.path())
+        .env("HOME", repo.path())
+        .args(["--skills-dir", &skills_dir(), "pick", "--from-diff=HEAD", "--top", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Task MainActor await"));
+}
+
+#[test]
+fn empty_staged_diff_is_an_error() {
+    let repo = repo_with_swift_change();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(repo.path())
+        .env("HOME", repo.path())
+        .args(["--skills-dir", &skills_dir(), "pick", "--from-diff"]);
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("No staged changes"));
+}
+
+#[test]
+fn revision_that_looks_like_an_option_is_rejected() {
+    let repo = repo_with_swift_change();
+    let target = repo.path().join("written-by-git");
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(repo.path())
+        .env("HOME", repo.path())
+        .arg("--skills-dir")
+        .arg(skills_dir())
+        .arg("pick")
+        .arg(format!("--from-diff=--output={}", target.display()));
+    cmd.assert()
+        .failure()
+        .stderr(str::contains("revisions cannot start with '-'"));
+    assert!(!target.exists(), "git must not have written {}", target.display());
+}
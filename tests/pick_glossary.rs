@@ -0,0 +1,40 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+/// Run `pick` with a home config pointing at a glossary file.
+fn pick_with_glossary(query: &str) -> String {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".config").join("codex-skills");
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(config_dir.join("config.toml"), "glossary = \"glossary.toml\"\n").unwrap();
+    fs::write(
+        config_dir.join("glossary.toml"),
+        "\"manejo de errores\" = [\"error handling\"]\n\"エラー処理\" = [\"error handling\"]\n",
+    )
+    .unwrap();
+
+    let skills = std::env::current_dir().unwrap().join("skills");
+    let output = cargo_bin_cmd!("codex-skills")
+        .current_dir(home.path())
+        .env("HOME", home.path())
+        .arg("--skills-dir")
+        .arg(&skills)
+        .args(["pick", query, "--top", "1"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "pick failed: {:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn spanish_query_reaches_english_skill() {
+    let out = pick_with_glossary("manejo de errores en Rust");
+    assert!(out.starts_with("1. Rust Best Practices Guide"), "got: {out}");
+}
+
+#[test]
+fn japanese_query_reaches_english_skill() {
+    let out = pick_with_glossary("Rustのエラー処理");
+    assert!(out.starts_with("1. Rust Best Practices Guide"), "got: {out}");
+}
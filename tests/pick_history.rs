@@ -0,0 +1,76 @@
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn pick_with_history(history: &str, extra: &[&str]) -> String {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.jsonl");
+    fs::write(&path, history).unwrap();
+    let output = cargo_bin_cmd!("codex-skills")
+        .args(["--skills-dir", "skills", "pick", "now add tests for it", "--top", "2"])
+        .arg("--history")
+        .arg(&path)
+        .args(extra)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "pick failed: {:?}", output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn earlier_turns_steer_a_vague_follow_up() {
+    let out = pick_with_history("rust error handling with thiserror\n", &[]);
+    assert!(out.starts_with("1. Rust Best Practices Guide"), "got: {out}");
+}
+
+#[test]
+fn previously_selected_skill_is_sticky() {
+    let out = pick_with_history(
+        "{\"query\": \"set up the app\", \"skill\": \"build-macos-apps\"}\n",
+        &["--explain"],
+    );
+    assert!(
+        out.contains("History: 1 turn, previous skill: build-macos-apps"),
+        "got: {out}"
+    );
+    assert!(out.contains("\n1. build-macos-apps"), "got: {out}");
+}
+
+#[test]
+fn invalid_history_entry_is_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.jsonl");
+    fs::write(&path, "{\"skill\": \"no query\"}\n").unwrap();
+    cargo_bin_cmd!("codex-skills")
+        .args(["--skills-dir", "skills", "pick", "tests"])
+        .arg("--history")
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicates::str::contains("Invalid history entry"));
+}
+
+#[test]
+fn history_skill_resolves_case_insensitively() {
+    let out = pick_with_history(
+        "{\"query\": \"set up the app\", \"skill\": \"Build-MacOS-Apps\"}\n",
+        &["--explain"],
+    );
+    assert!(out.contains("previous skill: build-macos-apps"), "got: {out}");
+    assert!(out.contains("\n1. build-macos-apps"), "got: {out}");
+}
+
+#[test]
+fn unknown_history_skill_is_reported_and_ignored() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("history.jsonl");
+    fs::write(&path, "{\"query\": \"set up the app\", \"skill\": \"macos\"}\n").unwrap();
+    cargo_bin_cmd!("codex-skills")
+        .args(["--skills-dir", "skills", "pick", "now add tests for it", "--explain"])
+        .arg("--history")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("previous skill: (none)"))
+        .stderr(predicates::str::contains("history skill 'macos' is not a skill name or alias"));
+}
@@ -1,6 +1,10 @@
+mod common;
+
 use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
 
+use common::skills_root;
+
 fn pick(query: &str) -> String {
     let mut cmd = cargo_bin_cmd!("codex-skills");
     cmd.arg("--skills-dir")
@@ -61,3 +65,104 @@ fn pick_show_explains_why_top_skill_won() {
                 .or(predicates::str::contains("summary hits")),
         );
 }
+
+/// Top result and reasoning for `query` against two skills whose summaries
+/// use only the base forms "design" and "testing".
+fn pick_inflected(query: &str) -> String {
+    let root = skills_root(&[
+        (
+            "ui-polish",
+            "name: ui-polish\ndescription: Visual design for screens",
+            "Spacing and color.",
+        ),
+        (
+            "qa-suite",
+            "name: qa-suite\ndescription: Automated testing for apps",
+            "Run the suite.",
+        ),
+    ]);
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .args(["pick", query, "--top", "1", "--show"]);
+    String::from_utf8(cmd.assert().success().get_output().stdout.clone()).unwrap()
+}
+
+#[test]
+fn inflected_forms_of_design_match_the_design_skill() {
+    for query in ["designing", "designs", "designer"] {
+        let out = pick_inflected(query);
+        assert!(out.starts_with("1. ui-polish"), "'{query}' got: {out}");
+        assert!(out.contains("summary hits=1"), "'{query}' should hit the summary: {out}");
+    }
+}
+
+#[test]
+fn plural_tests_matches_testing() {
+    let out = pick_inflected("tests");
+    assert!(out.starts_with("1. qa-suite"), "got: {out}");
+    assert!(out.contains("summary hits=1"), "should hit the summary: {out}");
+}
+
+#[test]
+fn pick_explain_prints_per_token_breakdown() {
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.args([
+        "--skills-dir",
+        "skills",
+        "pick",
+        "rust error handling",
+        "--top",
+        "1",
+        "--explain",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::contains("contribution"))
+        .stdout(predicates::str::contains("   rust "))
+        .stdout(predicates::str::contains("name"))
+        .stdout(predicates::str::contains("= total"));
+}
+
+#[test]
+fn misspelled_queries_are_corrected_and_reported() {
+    let out = pick("concurency actors");
+    assert!(
+        out.starts_with("Did you mean: concurrency actors"),
+        "missing correction hint: {out}"
+    );
+    assert!(out.contains("1. Swift Concurrency Expert Guide"), "got: {out}");
+}
+
+const ISSUE_BODY: &str = "## Bug report\n\n\
+Hi team, thanks for the great work on this project! I hope this is the right place to post.\n\
+When I run the app on my iPhone the data race detector reports a problem in an actor that\n\
+updates the UI from a background task. We upgraded to Swift 6 strict concurrency last week\n\
+and now the build emits \"Sendable\" warnings everywhere. Thanks again!\n";
+
+#[test]
+fn long_query_from_stdin_is_reduced_to_distinctive_terms() {
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.args(["--skills-dir", "skills", "pick", "-", "--top", "3"])
+        .write_stdin(ISSUE_BODY);
+    let out = String::from_utf8(cmd.assert().success().get_output().stdout.clone()).unwrap();
+
+    assert!(out.starts_with("Using distinctive terms: "), "got: {out}");
+    let terms = out.lines().next().unwrap();
+    assert!(terms.contains("concurr") && !terms.contains("thank"), "got: {terms}");
+    assert!(out.contains("Swift Concurrency Expert Guide"), "got: {out}");
+}
+
+#[test]
+fn query_file_is_read_as_the_task_description() {
+    let dir = tempfile::tempdir().unwrap();
+    let issue = dir.path().join("issue.md");
+    std::fs::write(&issue, "Rust error handling\nwith \"anyhow\" and thiserror\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.args(["--skills-dir", "skills", "pick", "--top", "1", "--query-file"])
+        .arg(&issue);
+    cmd.assert()
+        .success()
+        .stdout(predicates::str::starts_with("1. Rust Best Practices Guide"));
+}
@@ -56,3 +56,216 @@ fn respects_top_limit_in_default_output() {
         "top=2 should not print a third entry; got: {out}"
     );
 }
+
+#[test]
+fn routing_queries_keep_a_confident_match() {
+    // Queries the routing tests and the golden set rely on must clear the
+    // default min_confidence even when body or extras signals carry them.
+    let golden = std::fs::read_to_string("tests/golden/routing.yaml").unwrap();
+    let queries = golden
+        .lines()
+        .filter_map(|line| line.strip_prefix("- query: "))
+        .chain([
+            "debug failing tests",
+            "design",
+            "error handling",
+            "ios app screen design",
+            "brainstorming rename ideas",
+        ]);
+    for query in queries {
+        let out = pick(&[query, "--top", "3"]);
+        assert!(
+            out.starts_with("1. ") && !out.contains("nothing fits"),
+            "'{query}' lost its match: {out}"
+        );
+    }
+}
+
+#[test]
+fn leaders_past_the_confidence_cap_still_get_a_clear_verdict() {
+    // The top two of each display confidence 1.00 but differ in raw score.
+    for query in ["swift concurrency actors sendable", "swiftui", "ios"] {
+        let out = pick(&[query, "--top", "3"]);
+        assert!(out.contains("Verdict: clear winner"), "'{query}' got: {out}");
+    }
+}
+
+#[cfg(feature = "semantic")]
+#[test]
+fn semantic_mode_keeps_lexical_matches() {
+    // The similarity bonus may reorder results but never drops a lexical
+    // match or changes its verdict.
+    let top = |out: &str| {
+        let line = out.lines().find(|l| l.starts_with("1. ")).unwrap_or_default();
+        line.split(" (score").next().unwrap().to_string()
+    };
+    let verdict = |out: &str| {
+        let line = out.lines().find(|l| l.starts_with("Verdict: ")).unwrap_or_default();
+        line.split(" (").next().unwrap().to_string()
+    };
+    for query in ["accessibilty voiceover", "purchases subscriptions", "concurrent actor isolation"] {
+        let lexical = pick(&[query, "--top", "3"]);
+        let semantic = pick(&[query, "--top", "3", "--mode", "semantic"]);
+        assert!(!lexical.contains("nothing fits"), "'{query}' got: {lexical}");
+        assert_eq!(verdict(&lexical), verdict(&semantic), "'{query}' got: {semantic}");
+        assert_eq!(top(&lexical), top(&semantic), "'{query}' got: {semantic}");
+    }
+}
+
+#[test]
+fn avoid_when_triggers_keep_process_skills_off_mechanical_queries() {
+    let out = pick(&["mechanical rename of variables", "--top", "5"]);
+    assert!(
+        !out.contains("brainstorming"),
+        "brainstorming should be penalized for mechanical work: {out}"
+    );
+}
+
+#[test]
+fn show_output_reports_avoid_penalty() {
+    let out = pick(&["brainstorming rename ideas", "--top", "1", "--show"]);
+    assert!(
+        out.contains("avoid penalty=10"),
+        "missing avoid penalty signal: {out}"
+    );
+}
+
+#[test]
+fn avoid_when_triggers_match_whole_entries_only() {
+    // "version" alone is part of the "version bump" trigger, not the trigger itself.
+    let out = pick(&["brainstorm a versioning strategy for releases", "--top", "1", "--show"]);
+    assert!(
+        out.starts_with("1. brainstorming"),
+        "expected brainstorming to rank first, got: {out}"
+    );
+    assert!(
+        out.contains("avoid penalty=0"),
+        "a partial trigger should not be penalized: {out}"
+    );
+}
+
+#[test]
+fn extra_reference_docs_contribute_to_ranking() {
+    let out = pick(&["in-app purchase subscription receipts", "--top", "1", "--show"]);
+    assert!(
+        out.starts_with("1. build-iphone-apps"),
+        "expected storekit reference to lift build-iphone-apps, got: {out}"
+    );
+    assert!(
+        out.contains("(best: references/storekit.md)"),
+        "missing best extra doc: {out}"
+    );
+}
+
+#[test]
+fn results_carry_normalized_confidence_and_verdict() {
+    let out = pick(&["rust error handling", "--top", "2"]);
+    assert!(out.contains("confidence: 0."), "missing confidence: {out}");
+    assert!(
+        out.contains("Verdict: clear winner"),
+        "expected a clear winner: {out}"
+    );
+}
+
+#[test]
+fn close_runner_up_is_flagged_ambiguous() {
+    // Two Swift guides tie on raw score.
+    let out = pick(&["swift testing", "--top", "2"]);
+    assert!(out.contains("Verdict: ambiguous"), "expected ambiguity: {out}");
+}
+
+#[test]
+fn low_confidence_is_reported_as_nothing_fits() {
+    let out = pick(&["make a sandwich", "--top", "1"]);
+    assert!(out.contains("No good skill match"), "got: {out}");
+    assert!(out.contains("Verdict: nothing fits"), "got: {out}");
+}
+
+#[test]
+fn context_markers_boost_matching_skills_and_are_explained() {
+    let project = tempfile::tempdir().unwrap();
+    std::fs::write(project.path().join("Cargo.toml"), "[package]\n").unwrap();
+    let dir = project.path().to_str().unwrap();
+    let context = format!("--context={dir}");
+
+    let out = pick(&["error handling", "--top", "1", "--show", &context]);
+    assert!(
+        out.starts_with("1. Rust Best Practices Guide"),
+        "expected Cargo.toml to favor the Rust skill, got: {out}"
+    );
+    assert!(out.contains("context hits=1"), "missing context signal: {out}");
+
+    let out = pick(&["error handling", "--top", "1", "--explain", &context]);
+    assert!(
+        out.contains(&format!("Context markers in {dir}: Cargo.toml")),
+        "missing detected markers: {out}"
+    );
+    assert!(out.contains("+ context boost"), "missing context row: {out}");
+}
+
+#[test]
+fn bare_context_flag_does_not_take_the_query() {
+    let out = pick(&["--context", "rust error handling", "--top", "1"]);
+    assert!(
+        out.starts_with("1. Rust Best Practices Guide"),
+        "the query after --context should still be the query: {out}"
+    );
+}
+
+#[test]
+fn split_ranks_each_intent_and_merges_picks() {
+    let out = pick(&[
+        "design the onboarding screens and then write XCTest coverage",
+        "--split",
+        "--top",
+        "2",
+    ]);
+    assert!(
+        out.starts_with("Intent 1: design the onboarding screens\n"),
+        "got: {out}"
+    );
+    assert!(out.contains("Intent 2: write XCTest coverage\n"), "got: {out}");
+    assert!(out.contains("Skills to use: "), "missing merged picks: {out}");
+}
+
+#[test]
+fn split_merges_a_skill_answering_several_intents() {
+    let out = pick(&["rust error handling. rust testing", "--split", "--top", "1"]);
+    assert!(
+        out.contains("Skills to use: Rust Best Practices Guide (intents 1, 2)"),
+        "expected one merged pick: {out}"
+    );
+}
+
+#[test]
+fn split_keeps_file_names_whole_and_hides_zero_scores() {
+    let out = pick(&["update Cargo.toml and fix the build", "--split", "--top", "40"]);
+    assert!(out.starts_with("Intent 1: update Cargo.toml\n"), "got: {out}");
+    assert!(out.contains("Intent 2: fix the build\n"), "got: {out}");
+    assert!(!out.contains("Intent 3"), "got: {out}");
+    assert!(!out.contains("(score: 0,"), "zero scores should be hidden: {out}");
+}
+
+#[test]
+fn diversity_reorders_top_results_but_keeps_verdict() {
+    let names = |out: &str| -> Vec<String> {
+        out.lines()
+            .filter(|l| l.starts_with(|c: char| c.is_ascii_digit()))
+            .map(|l| l.split(" (score").next().unwrap().to_string())
+            .collect()
+    };
+    let plain = pick(&["ios app screens", "--top", "3"]);
+    let diverse = pick(&["ios app screens", "--top", "3", "--diversity", "0.3"]);
+    assert_eq!(names(&plain)[0], names(&diverse)[0]);
+    assert_ne!(names(&plain), names(&diverse), "got: {diverse}");
+    assert!(plain.contains("Verdict: ambiguous"), "got: {plain}");
+    assert!(diverse.contains("Verdict: ambiguous"), "got: {diverse}");
+}
+
+#[test]
+fn diversity_outside_unit_range_is_rejected() {
+    cargo_bin_cmd!("codex-skills")
+        .args(["--skills-dir", "skills", "pick", "rust", "--diversity", "1.5"])
+        .assert()
+        .failure();
+}
@@ -17,3 +17,44 @@ fn pick_show_includes_additional_markdown_files() {
         .success()
         .stdout(str::contains("Pressure Test 1: Emergency Production Fix"));
 }
+
+#[test]
+fn pick_lists_best_sub_document_per_result() {
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.args([
+        "--skills-dir",
+        "skills",
+        "pick",
+        "build-iphone-apps push notifications apns device token",
+        "--top",
+        "1",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(str::contains("best doc: references/push-notifications.md"));
+}
+
+#[test]
+fn pick_focused_prints_only_best_sub_document() {
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.args([
+        "--skills-dir",
+        "skills",
+        "pick",
+        "build-iphone-apps push notifications apns device token",
+        "--top",
+        "1",
+        "--focused",
+    ]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let out = String::from_utf8(output).unwrap();
+
+    assert!(
+        out.contains("---------------------------------------- references/push-notifications.md"),
+        "focused output should print the best doc: {out}"
+    );
+    assert!(
+        !out.contains("references/storekit.md\n"),
+        "focused output should skip other docs: {out}"
+    );
+}
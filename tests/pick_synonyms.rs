@@ -0,0 +1,56 @@
+mod common;
+
+use std::fs;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::str;
+
+use common::skills_root;
+
+fn fixture() -> tempfile::TempDir {
+    skills_root(&[
+        (
+            "mobile-polish",
+            "name: mobile-polish\ndescription: Final polish pass for iphone apps\ntags:\n  - iphone\n  - polish\naliases:\n  - handset finish",
+            "Check spacing, haptics and launch screens.",
+        ),
+        (
+            "web-layout",
+            "name: web-layout\ndescription: Responsive layout for websites\ntags:\n  - css\n  - layout",
+            "Grid and flexbox layouts for browsers.",
+        ),
+    ])
+}
+
+#[test]
+fn config_synonyms_expand_query_tokens() {
+    let root = fixture();
+    fs::write(
+        root.path().join(".codex-skills.toml"),
+        "[synonyms]\nios = [\"iphone\"]\n",
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .args(["pick", "ios", "--top", "1", "--show"]);
+    cmd.assert()
+        .success()
+        .stdout(str::starts_with("1. mobile-polish"))
+        .stdout(str::contains("expanded hits=1"));
+}
+
+#[test]
+fn frontmatter_aliases_route_to_skill() {
+    let root = fixture();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .args(["pick", "handset finish", "--top", "1", "--show"]);
+    cmd.assert()
+        .success()
+        .stdout(str::starts_with("1. mobile-polish"))
+        .stdout(str::contains("alias hits=2"));
+}
@@ -0,0 +1,76 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn run(args: &[&str]) -> String {
+    let output = cargo_bin_cmd!("codex-skills")
+        .args(["--skills-dir", "skills"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?} failed: {:?}", args, output);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn ranked_names(out: &str) -> Vec<&str> {
+    out.lines()
+        .filter(|l| l.starts_with(|c: char| c.is_ascii_digit()))
+        .filter_map(|l| l.split_once(". ")?.1.split(" (score").next())
+        .collect()
+}
+
+#[test]
+fn exclusion_drops_skills_containing_the_term() {
+    let plain = run(&["pick", "swiftui app", "--top", "10"]);
+    assert!(ranked_names(&plain).contains(&"build-macos-apps"), "got: {plain}");
+
+    let out = run(&["pick", "swiftui app -macos", "--top", "10", "--explain"]);
+    assert!(out.contains("Query filters: -macos"), "got: {out}");
+    assert!(!ranked_names(&out).contains(&"build-macos-apps"), "got: {out}");
+}
+
+#[test]
+fn leading_exclusion_is_read_as_query_text() {
+    let out = run(&["pick", "-macos swiftui", "--top", "10"]);
+    assert!(!out.contains("build-macos-apps"), "got: {out}");
+}
+
+#[test]
+fn field_prefix_restricts_to_skills_with_the_term_in_that_field() {
+    let out = run(&["pick", "name:rust testing", "--top", "5"]);
+    assert_eq!(ranked_names(&out), vec!["Rust Best Practices Guide"], "got: {out}");
+}
+
+#[test]
+fn quoted_phrase_must_appear_verbatim() {
+    let out = run(&["search", "\"push notifications\" -macos", "-c", "0"]);
+    assert!(out.contains("software-mobile"), "got: {out}");
+    assert!(!out.contains("build-macos-apps"), "got: {out}");
+}
+
+#[test]
+fn search_without_text_is_rejected() {
+    cargo_bin_cmd!("codex-skills")
+        .args(["--skills-dir", "skills", "search", "tag:swift"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn phrases_found_only_in_reference_docs_keep_the_skill() {
+    let out = run(&["pick", "\"time profiler\"", "--top", "10"]);
+    let names = ranked_names(&out);
+    assert!(names.contains(&"build-iphone-apps"), "got: {out}");
+    assert!(names.contains(&"build-macos-apps"), "got: {out}");
+}
+
+#[test]
+fn text_from_stdin_is_not_parsed_as_query_syntax() {
+    let output = cargo_bin_cmd!("codex-skills")
+        .args(["--skills-dir", "skills", "pick", "-", "--top", "3"])
+        .write_stdin("SwiftUI macOS app crashes with \"Thread 1: EXC_BAD_ACCESS\" -O2 build\n")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let out = String::from_utf8(output.stdout).unwrap();
+    assert!(!out.contains("nothing fits"), "got: {out}");
+    assert!(ranked_names(&out).contains(&"build-macos-apps"), "got: {out}");
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use assert_cmd::cargo::cargo_bin_cmd;
+use predicates::prelude::*;
+use predicates::str;
+
+use common::{skills_root, write_skill};
+
+fn fixture() -> tempfile::TempDir {
+    skills_root(&[
+        (
+            "release-notes",
+            "name: release-notes\ndescription: Draft notes for a new version\nexamples:\n  - write the changelog for this sprint\n  - summarize merged pull requests",
+            "Group changes by feature and fix.",
+        ),
+        (
+            "db-migrations",
+            "name: db-migrations\ndescription: Evolve the database schema safely\nexamples:\n  - add a column to the users table\n  - roll back a failed migration",
+            "Write reversible migrations.",
+        ),
+    ])
+}
+
+#[test]
+fn train_writes_model_next_to_skills_dir() {
+    let root = fixture();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .arg("train");
+    cmd.assert()
+        .success()
+        .stdout(str::contains("Trained on 4 examples across 2 skills"));
+    assert!(root.path().join("skills.model.json").exists());
+}
+
+#[test]
+fn trained_model_adds_a_score_signal_to_pick() {
+    let root = fixture();
+    cargo_bin_cmd!("codex-skills")
+        .current_dir(root.path())
+        .env("HOME", root.path())
+        .arg("train")
+        .assert()
+        .success();
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .args(["pick", "changelog for pull requests", "--top", "1", "--show"]);
+    cmd.assert()
+        .success()
+        .stdout(str::starts_with("1. release-notes"))
+        .stdout(str::contains("model score=9"));
+}
+
+#[test]
+fn stale_model_is_ignored() {
+    let root = fixture();
+    cargo_bin_cmd!("codex-skills")
+        .current_dir(root.path())
+        .env("HOME", root.path())
+        .arg("train")
+        .assert()
+        .success();
+    write_skill(
+        root.path(),
+        "api-docs",
+        "name: api-docs\ndescription: Document public endpoints",
+        "Describe every request and response.",
+    );
+
+    let mut cmd = cargo_bin_cmd!("codex-skills");
+    cmd.current_dir(root.path())
+        .env("HOME", root.path())
+        .args(["pick", "changelog", "--top", "1", "--show"]);
+    cmd.assert()
+        .success()
+        .stderr(str::contains("model is out of date"))
+        .stdout(str::contains("model score").not());
+}